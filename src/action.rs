@@ -4,9 +4,21 @@ use serde::{
     de::{self, Deserializer, Visitor},
     Deserialize, Serialize,
 };
-use strum::Display;
+use strum::{Display, VariantNames};
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Display, Deserialize)]
+use crate::client::AirflowClient;
+use crate::config::Config;
+use crate::models::clear::{ClearRequest, TaskInstanceReference};
+use crate::models::dag::Dag;
+use crate::models::dag_run::DagRun;
+use crate::models::dag_runs::DagRuns;
+use crate::models::dags::Dags;
+use crate::models::mark::MarkRequest;
+use crate::models::pools::Pools;
+use crate::models::task::Task;
+use crate::models::tasks::Tasks;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Display, Deserialize, VariantNames)]
 pub enum Action {
     Tick,
     Render,
@@ -16,20 +28,85 @@ pub enum Action {
     Quit,
     Refresh,
     Error(String),
+    Info(String),
     Help,
     DagRun,
     Code,
     Clear,
+    MarkSuccess,
+    MarkFailed,
+    ExportLogs,
+    ExportLogsTo(String),
+    ExportTable(String),
     Next,
     Previous,
     Search,
     SubmitSearch,
     ClearSearch,
+    SearchText(String),
+    NextMatch,
+    PreviousMatch,
     Task,
     Log,
     NextTryNumber,
+    FollowLog,
+    ToggleLogHeaders,
+    NextLogLevel,
     PreviousTryNumber,
     Down,
     Up,
     Command,
+    GotoLine(usize),
+    Runs(Option<String>),
+    Dag,
+    OpenDag,
+    Pool,
+    SwitchContext(String),
+    #[serde(skip)]
+    ContextSwitched(Box<Config>, Box<AirflowClient>),
+    LoadDagRunsPage,
+    Trigger,
+    TogglePause,
+    SetDagPaused(String, bool),
+    Accept,
+    Cancel,
+    #[serde(skip)]
+    Confirm(String, Box<Action>),
+    TriggerDag(String),
+    #[serde(skip)]
+    DagRunsLoaded(Option<String>, DagRuns),
+    #[serde(skip)]
+    DagRunsPageLoaded(Option<String>, DagRuns),
+    #[serde(skip)]
+    DagRunsPageFailed(String),
+    #[serde(skip)]
+    TasksLoaded(String, String, Tasks),
+    #[serde(skip)]
+    DagRunTriggered(Box<DagRun>),
+    #[serde(skip)]
+    DagsLoaded(Dags),
+    #[serde(skip)]
+    DagUpdated(Box<Dag>),
+    #[serde(skip)]
+    PreviewClear(Box<ClearRequest>),
+    #[serde(skip)]
+    ClearPreviewLoaded(Box<ClearRequest>, Vec<TaskInstanceReference>),
+    #[serde(skip)]
+    ClearPreviewFailed(Box<ClearRequest>, String),
+    #[serde(skip)]
+    ApplyClear(Box<ClearRequest>),
+    #[serde(skip)]
+    ApplyMark(Box<MarkRequest>),
+    #[serde(skip)]
+    CodeLoaded(String, String),
+    #[serde(skip)]
+    PoolsLoaded(Pools),
+    #[serde(skip)]
+    LogLoaded(Box<Task>, usize, String),
+    #[serde(skip)]
+    LogAppended(String, bool),
+    #[serde(skip)]
+    VersionDetected(String),
+    #[serde(skip)]
+    CommandFailed(String),
 }
@@ -1,28 +1,35 @@
 use std::cell::RefCell;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
 use crate::main_layout::{self, Chunk};
 use crate::mode::ObservableMode;
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{eyre, Result};
 use crossterm::event::KeyEvent;
 use log::log;
 use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::prelude::Rect;
-use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc;
-use tokio::time::Instant;
+use tokio::sync::mpsc::{self, UnboundedSender};
 
+use crate::client::AirflowClient;
 use crate::components::ascii::Ascii;
+use crate::components::clear_confirm::ClearConfirm;
 use crate::components::command::Command;
 use crate::components::command_search::CommandSearch;
+use crate::components::confirm::Confirm;
 use crate::components::context_informations::ContextInformation;
+use crate::components::mark_confirm::MarkConfirm;
 use crate::components::shortcut::Shortcut;
 use crate::components::status_bar::StatusBar;
 use crate::components::table_dag_runs::TableDagRuns;
 use crate::main_layout::MainLayout;
-use crate::models::dag_run::DagRun;
+use crate::models::dag_run::{DagRun, DagRunTrigger};
 use crate::models::dag_runs::DagRuns;
+use crate::models::dags::Dags;
+use crate::models::mark::MarkState;
+use crate::utils::edit_in_editor;
+use crate::worker::Worker;
 use crate::{
     action::Action,
     components::{fps::FpsCounter, Component},
@@ -40,10 +47,11 @@ pub struct App {
     pub observable_mode: ObservableMode,
     pub main_layout: Rc<RefCell<MainLayout>>,
     pub last_tick_key_events: Vec<KeyEvent>,
-    pub last_dag_runs_call: Instant,
-    pub last_task_call: Option<Instant>,
     pub dag_runs: DagRuns,
-    client: Client,
+    pub loading_dag_runs_page: bool,
+    client: AirflowClient,
+    // Context being switched to, until its secrets are resolved
+    pending_context: Option<String>,
     context_information: ContextInformation,
     shortcut: Shortcut,
     ascii: Ascii,
@@ -51,14 +59,23 @@ pub struct App {
     status_bar: StatusBar,
     command_search: CommandSearch,
     command: Command,
+    confirm: Confirm,
+    clear_confirm: ClearConfirm,
+    mark_confirm: MarkConfirm,
+    mode_before_confirm: Mode,
+    mode_before_search: Mode,
+    mode_before_command: Mode,
 }
 
 impl App {
-    pub async fn new(tick_rate: f64, frame_rate: f64) -> Result<Self> {
+    pub async fn new(tick_rate: f64, frame_rate: f64, context: Option<String>) -> Result<Self> {
         let fps = FpsCounter::default();
-        let config = Config::new()?;
+        let mut config = Config::new()?;
+        config
+            .use_initial_context(context.as_deref())
+            .map_err(|e| eyre!(e))?;
         let mode = Mode::DagRun;
-        let client = Client::new();
+        let client = AirflowClient::new(&config.airflow)?;
         Ok(Self {
             tick_rate,
             frame_rate,
@@ -68,10 +85,10 @@ impl App {
             config,
             main_layout: Rc::new(RefCell::new(MainLayout::new())),
             last_tick_key_events: Vec::new(),
-            last_dag_runs_call: Instant::now(),
-            last_task_call: None,
             dag_runs: DagRuns::new(),
+            loading_dag_runs_page: false,
             client,
+            pending_context: None,
             context_information: ContextInformation::new(),
             shortcut: Shortcut::new(),
             ascii: Ascii::new(),
@@ -79,22 +96,49 @@ impl App {
             status_bar: StatusBar::new(),
             command_search: CommandSearch::new(),
             command: Command::new(),
+            confirm: Confirm::new(),
+            clear_confirm: ClearConfirm::new(),
+            mark_confirm: MarkConfirm::new(),
+            mode_before_confirm: Mode::DagRun,
+            mode_before_search: Mode::Log,
+            mode_before_command: Mode::DagRun,
         })
     }
 
+    /// Fetch the displayed try of the log of the selected task in the background, the
+    /// previous log is dropped meanwhile.
+    fn load_log(&mut self, worker: &Worker) {
+        let Some(task) = self.table_dag_runs.get_selected_task() else {
+            return;
+        };
+        worker.load_log(task.clone(), self.table_dag_runs.try_number);
+        self.table_dag_runs.set_log(String::new());
+    }
+
+    /// The dag selected in the dag table, or the dag of the selected dag run.
+    fn get_selected_dag_id(&self) -> Option<String> {
+        if self.observable_mode.get() == Mode::Dag {
+            self.table_dag_runs
+                .get_selected_dag()
+                .map(|dag| dag.dag_id.clone())
+        } else {
+            self.table_dag_runs
+                .get_selected_dag_run()
+                .map(|dag_run| dag_run.dag_id.clone())
+        }
+    }
+
     pub async fn run(&mut self) -> Result<()> {
         let (action_tx, mut action_rx) = mpsc::unbounded_channel();
-        self.dag_runs
-            .set_dag_runs(
-                &self.client,
-                &self.config.airflow.username,
-                &self.config.airflow.password,
-                &self.config.airflow.host,
-            )
-            .await?;
-        self.last_dag_runs_call = Instant::now();
-        // Set the initial dag_runs state for the table widget
-        self.table_dag_runs.set_dag_runs(self.dag_runs.clone());
+        // Poll airflow in the background, the dag runs are loaded as soon as the worker starts
+        let mut worker = Worker::new(
+            self.client.clone(),
+            self.config.get_page_size(),
+            action_tx.clone(),
+        );
+        worker.watch_dag_runs(None);
+        worker.watch_dags();
+        worker.detect_version();
         let mut tui = tui::Tui::new()?;
         // tui.mouse(true);
         tui.enter()?;
@@ -134,41 +178,6 @@ impl App {
             .set_refresh_layout_fn(move |mode| main_layout_rc.borrow_mut().set_main_layout(&mode));
 
         loop {
-            if self.last_dag_runs_call.elapsed().as_secs() == 3 {
-                self.dag_runs
-                    .set_dag_runs(
-                        &self.client,
-                        &self.config.airflow.username,
-                        &self.config.airflow.password,
-                        &self.config.airflow.host,
-                    )
-                    .await?;
-                self.last_dag_runs_call = Instant::now();
-                self.table_dag_runs.set_dag_runs(self.dag_runs.clone());
-            }
-
-            // If the task mode is selected, then fetch the tasks for the selected dag_run
-            if self.observable_mode.get() == Mode::Task {
-                if let Some(last_task_call) = self.last_task_call {
-                    if last_task_call.elapsed().as_secs() >= 2 {
-                        self.table_dag_runs.tasks = Some(
-                            self.dag_runs
-                                .get_task(
-                                    &self.client,
-                                    &self.config.airflow,
-                                    &self.config.airflow.username,
-                                    &self.config.airflow.password,
-                                    &self.config.airflow.host,
-                                    self.table_dag_runs.table_state.selected().unwrap_or(0),
-                                )
-                                .await?,
-                        );
-                        self.last_task_call = Some(Instant::now());
-                    }
-                } else {
-                    self.last_task_call = Some(Instant::now());
-                }
-            }
             if let Some(e) = tui.next().await {
                 match e {
                     tui::Event::Quit => action_tx.send(Action::Quit)?,
@@ -212,13 +221,33 @@ impl App {
                 if let Some(action) = self.command.handle_events(Some(e.clone()))? {
                     action_tx.send(action)?;
                 }
+                if let Some(action) = self.confirm.handle_events(Some(e.clone()))? {
+                    action_tx.send(action)?;
+                }
+                if let Some(action) = self.clear_confirm.handle_events(Some(e.clone()))? {
+                    action_tx.send(action)?;
+                }
+                if let Some(action) = self.mark_confirm.handle_events(Some(e.clone()))? {
+                    action_tx.send(action)?;
+                }
                 if let Some(action) = self.table_dag_runs.handle_events(Some(e.clone()))? {
                     action_tx.send(action)?;
                 }
             }
 
             while let Ok(action) = action_rx.try_recv() {
-                if action != Action::Tick && action != Action::Render {
+                if !matches!(
+                    action,
+                    Action::Tick
+                        | Action::Render
+                        | Action::DagRunsLoaded(..)
+                        | Action::DagRunsPageLoaded(..)
+                        | Action::DagsLoaded(_)
+                        | Action::TasksLoaded(..)
+                        | Action::CodeLoaded(..)
+                        | Action::LogLoaded(..)
+                        | Action::LogAppended(..)
+                ) {
                     log::debug!("{action:?}");
                 }
                 match action {
@@ -271,7 +300,8 @@ impl App {
                                     .unwrap();
                             }
 
-                            if self.observable_mode.get() == Mode::Search {
+                            if matches!(self.observable_mode.get(), Mode::Search | Mode::TextSearch)
+                            {
                                 let r = self
                                     .command_search
                                     .draw(f, self.main_layout.borrow().get_chunk(Chunk::Command));
@@ -328,8 +358,61 @@ impl App {
                                     .send(Action::Error(format!("Failed to draw: {:?}", e)))
                                     .unwrap();
                             }
+
+                            if self.observable_mode.get() == Mode::Confirm {
+                                let r = self
+                                    .confirm
+                                    .draw(f, self.main_layout.borrow().get_chunk(Chunk::Table));
+                                if let Err(e) = r {
+                                    action_tx
+                                        .send(Action::Error(format!("Failed to draw: {:?}", e)))
+                                        .unwrap();
+                                }
+                            }
+
+                            if self.observable_mode.get() == Mode::Clear {
+                                let r = self
+                                    .clear_confirm
+                                    .draw(f, self.main_layout.borrow().get_chunk(Chunk::Table));
+                                if let Err(e) = r {
+                                    action_tx
+                                        .send(Action::Error(format!("Failed to draw: {:?}", e)))
+                                        .unwrap();
+                                }
+                            }
+
+                            if self.observable_mode.get() == Mode::Mark {
+                                let r = self
+                                    .mark_confirm
+                                    .draw(f, self.main_layout.borrow().get_chunk(Chunk::Table));
+                                if let Err(e) = r {
+                                    action_tx
+                                        .send(Action::Error(format!("Failed to draw: {:?}", e)))
+                                        .unwrap();
+                                }
+                            }
                         })?;
                     }
+                    // The log and the source code are searched in place
+                    Action::Search
+                        if matches!(self.observable_mode.get(), Mode::Log | Mode::Code) =>
+                    {
+                        self.mode_before_search = self.observable_mode.get();
+                        self.observable_mode.set_mode(Mode::TextSearch);
+                        self.status_bar.register_mode(self.observable_mode.get());
+                    }
+                    Action::SearchText(ref pattern) => {
+                        self.table_dag_runs.search_text(pattern);
+                    }
+                    Action::SubmitSearch if self.observable_mode.get() == Mode::TextSearch => {
+                        self.observable_mode.set_mode(self.mode_before_search);
+                        self.status_bar.register_mode(self.observable_mode.get());
+                    }
+                    Action::ClearSearch if self.observable_mode.get() == Mode::TextSearch => {
+                        self.table_dag_runs.search_text("");
+                        self.observable_mode.set_mode(self.mode_before_search);
+                        self.status_bar.register_mode(self.observable_mode.get());
+                    }
                     Action::Search => {
                         self.status_bar.mode_breadcrumb.push(Mode::DagRun);
                         self.observable_mode.set_mode(Mode::Search);
@@ -337,12 +420,14 @@ impl App {
                         self.observable_mode.set_mode(Mode::Search);
                     }
                     Action::Command => {
+                        self.mode_before_command = self.observable_mode.get();
                         self.status_bar.mode_breadcrumb.push(Mode::DagRun);
                         self.observable_mode.set_mode(Mode::Command);
                         self.status_bar.register_mode(self.observable_mode.get());
                         self.observable_mode.set_mode(Mode::Command);
                     }
                     Action::DagRun => {
+                        worker.unwatch_tasks();
                         self.status_bar.mode_breadcrumb.clear();
                         self.command.command = None;
                         self.observable_mode.set_mode(Mode::DagRun);
@@ -351,74 +436,180 @@ impl App {
                         self.observable_mode.set_mode(Mode::DagRun);
                     }
                     Action::Code => {
+                        let Some(dag_id) = self
+                            .table_dag_runs
+                            .get_selected_dag_run()
+                            .map(|dag_run| dag_run.dag_id.clone())
+                        else {
+                            action_tx.send(Action::Error(String::from("No dag run selected")))?;
+                            continue;
+                        };
                         self.observable_mode.set_mode(Mode::Code);
                         self.status_bar.mode_breadcrumb.push(Mode::DagRun);
                         self.status_bar.register_mode(self.observable_mode.get());
                         self.table_dag_runs
                             .handle_mode(self.observable_mode.get())?;
-                        let source_code = self.table_dag_runs.dag_runs.dag_runs
-                            [self.table_dag_runs.table_state.selected().unwrap()]
-                        .get_source_code(&self.client, &self.config.airflow)
-                        .await?;
-                        self.table_dag_runs.code = source_code.clone();
+                        // The source code of the previous dag is dropped until the new one is loaded
+                        worker.load_dag_source(dag_id);
+                        self.table_dag_runs.set_code(String::new());
+                        self.table_dag_runs.goto_line = None;
                         self.observable_mode.set_mode(Mode::Code);
                     }
-                    Action::Clear => {
-                        if self.observable_mode.get() == Mode::DagRun
-                            && self.table_dag_runs.table_state.selected().is_some()
-                        {
-                            self.dag_runs.dag_runs
-                                [self.table_dag_runs.table_state.selected().unwrap()]
-                            .clear(
-                                &self.client,
-                                &self.config.airflow,
-                                &self.config.airflow.username,
-                                &self.config.airflow.password,
-                                &self.config.airflow.host,
-                            )
-                            .await?;
+                    // A response still in flight for the previously selected dag is dropped
+                    Action::CodeLoaded(ref dag_id, ref code)
+                        if self
+                            .table_dag_runs
+                            .get_selected_dag_run()
+                            .is_some_and(|dag_run| &dag_run.dag_id == dag_id) =>
+                    {
+                        self.table_dag_runs.set_code(code.clone());
+                    }
+                    Action::ExportLogs => {
+                        let dir = self.config.get_export_dir();
+                        action_tx.send(Action::ExportLogsTo(dir.display().to_string()))?;
+                    }
+                    Action::ExportLogsTo(ref dir) => {
+                        match self.table_dag_runs.get_selected_dag_run() {
+                            Some(dag_run) => {
+                                worker.export_logs(dag_run.clone(), PathBuf::from(dir))
+                            }
+                            None => action_tx
+                                .send(Action::Error(String::from("No dag run selected")))?,
                         }
-
-                        if self.observable_mode.get() == Mode::Task
-                            && self.table_dag_runs.table_state.selected().is_some()
-                        {
-                            self.table_dag_runs.tasks.as_mut().unwrap().task_instances
-                                [self.table_dag_runs.table_tasks_state.selected().unwrap()]
-                            .clear(
-                                &self.client,
-                                &self.config.airflow,
-                                &self.config.airflow.username,
-                                &self.config.airflow.password,
-                                &self.config.airflow.host,
-                            )
-                            .await?;
+                        if self.observable_mode.get() == Mode::Command {
+                            action_tx.send(Action::DagRun)?;
                         }
                     }
+                    Action::ExportTable(ref path) => {
+                        // The table displayed before opening the command bar is exported
+                        let mode = if self.observable_mode.get() == Mode::Command {
+                            self.status_bar.mode_breadcrumb.pop();
+                            self.observable_mode.set_mode(self.mode_before_command);
+                            self.status_bar.register_mode(self.observable_mode.get());
+                            self.mode_before_command
+                        } else {
+                            self.observable_mode.get()
+                        };
+                        match self.table_dag_runs.export_table(mode, Path::new(path)) {
+                            Ok(count) => action_tx.send(Action::Info(format!(
+                                "Exported {} rows to {}",
+                                count, path
+                            )))?,
+                            Err(e) => action_tx
+                                .send(Action::Error(format!("Failed to export table: {}", e)))?,
+                        }
+                    }
+                    Action::GotoLine(number)
+                        if self.observable_mode.get() == Mode::Command
+                            && self.mode_before_command == Mode::Code =>
+                    {
+                        self.status_bar.mode_breadcrumb.pop();
+                        self.observable_mode.set_mode(Mode::Code);
+                        self.status_bar.register_mode(self.observable_mode.get());
+                        self.table_dag_runs.go_to_line(number);
+                    }
+                    Action::GotoLine(_) => {
+                        action_tx.send(Action::Error(String::from(
+                            "Lines can only be jumped to in the source code",
+                        )))?;
+                    }
+                    Action::Clear => {
+                        let request = match self.observable_mode.get() {
+                            Mode::DagRun => self
+                                .table_dag_runs
+                                .get_selected_dag_run()
+                                .map(|dag_run| dag_run.clear_request()),
+                            Mode::Task => self
+                                .table_dag_runs
+                                .get_selected_task()
+                                .map(|task| task.clear_request()),
+                            _ => None,
+                        };
+                        let Some(request) = request else {
+                            action_tx.send(Action::Error(String::from("Nothing to clear")))?;
+                            continue;
+                        };
+                        // Nothing is cleared before the user saw the dry run of the clear
+                        self.mode_before_confirm = self.observable_mode.get();
+                        self.clear_confirm.open(request.clone());
+                        self.observable_mode.set_mode(Mode::Clear);
+                        self.status_bar.register_mode(self.observable_mode.get());
+                        worker.preview_clear(request);
+                    }
+                    Action::PreviewClear(ref request) => {
+                        worker.preview_clear(*request.clone());
+                    }
+                    Action::ClearPreviewLoaded(ref request, ref task_instances) => {
+                        self.clear_confirm
+                            .set_preview(request, task_instances.clone());
+                    }
+                    Action::ClearPreviewFailed(ref request, ref message) => {
+                        self.clear_confirm
+                            .set_preview_failed(request, message.clone());
+                    }
+                    Action::ApplyClear(ref request) => {
+                        self.clear_confirm.close();
+                        self.observable_mode.set_mode(self.mode_before_confirm);
+                        self.status_bar.register_mode(self.observable_mode.get());
+                        worker.clear(*request.clone());
+                    }
+                    Action::MarkSuccess | Action::MarkFailed => {
+                        let state = if action == Action::MarkSuccess {
+                            MarkState::Success
+                        } else {
+                            MarkState::Failed
+                        };
+                        let request = match self.observable_mode.get() {
+                            Mode::DagRun => self
+                                .table_dag_runs
+                                .get_selected_dag_run()
+                                .map(|dag_run| dag_run.mark_request(state)),
+                            Mode::Task => self
+                                .table_dag_runs
+                                .get_selected_task()
+                                .map(|task| task.mark_request(state)),
+                            _ => None,
+                        };
+                        let Some(request) = request else {
+                            action_tx.send(Action::Error(String::from("Nothing to mark")))?;
+                            continue;
+                        };
+                        self.mode_before_confirm = self.observable_mode.get();
+                        self.mark_confirm.open(request);
+                        self.observable_mode.set_mode(Mode::Mark);
+                        self.status_bar.register_mode(self.observable_mode.get());
+                    }
+                    Action::ApplyMark(ref request) => {
+                        self.mark_confirm.close();
+                        self.observable_mode.set_mode(self.mode_before_confirm);
+                        self.status_bar.register_mode(self.observable_mode.get());
+                        worker.mark(*request.clone());
+                    }
                     Action::Task => {
                         self.status_bar.mode_breadcrumb.clear();
                         self.status_bar.mode_breadcrumb.push(Mode::DagRun);
-                        if self.table_dag_runs.table_state.selected().is_none() {
+                        let Some(dag_run) = self.table_dag_runs.get_selected_dag_run().cloned()
+                        else {
                             self.observable_mode.set_mode(Mode::DagRun);
                             self.observable_mode.set_mode(Mode::DagRun);
                             break;
-                        }
+                        };
                         self.observable_mode.set_mode(Mode::Task);
-                        self.table_dag_runs.table_tasks_state.select(Some(0));
-                        self.table_dag_runs.tasks = Some(
-                            self.dag_runs
-                                .get_task(
-                                    &self.client,
-                                    &self.config.airflow,
-                                    &self.config.airflow.username,
-                                    &self.config.airflow.password,
-                                    &self.config.airflow.host,
-                                    self.table_dag_runs.table_state.selected().unwrap_or(0),
-                                )
-                                .await?,
-                        );
+                        // The tasks of the previous dag run are dropped until the new ones are loaded
+                        if worker.watch_tasks(dag_run) {
+                            self.table_dag_runs.tasks = None;
+                            self.table_dag_runs.table_tasks_state.select(Some(0));
+                        }
                         self.status_bar.register_mode(self.observable_mode.get());
                     }
                     Action::Log => {
+                        if self.table_dag_runs.tasks.is_none() {
+                            action_tx
+                                .send(Action::Error(String::from("Tasks are still loading")))?;
+                            continue;
+                        }
+                        worker.unfollow_log();
+                        self.table_dag_runs.following_log = false;
                         self.status_bar.mode_breadcrumb.clear();
                         self.status_bar.mode_breadcrumb.push(Mode::DagRun);
                         self.status_bar.mode_breadcrumb.push(Mode::Task);
@@ -427,70 +618,273 @@ impl App {
                             .handle_mode(self.observable_mode.get())?;
                         self.status_bar.register_mode(self.observable_mode.get());
                         if self.table_dag_runs.table_state.selected().is_some() {
-                            let log = self.table_dag_runs.tasks.as_mut().unwrap().task_instances
-                                [self.table_dag_runs.table_tasks_state.selected().unwrap()]
-                            .get_logs(
-                                &self.client,
-                                &self.config.airflow,
-                                &self.config.airflow.username,
-                                &self.config.airflow.password,
-                                &self.config.airflow.host,
-                                self.table_dag_runs.try_number,
-                            )
-                            .await?;
-                            self.table_dag_runs.log = log;
+                            self.load_log(&worker);
                         };
                     }
-                    Action::NextTryNumber => {
+                    Action::NextTryNumber
                         if self.table_dag_runs.table_tasks_state.selected().is_some()
                             && self.observable_mode.get() == Mode::Log
                             && self.table_dag_runs.tasks.as_ref().unwrap().task_instances
                                 [self.table_dag_runs.table_tasks_state.selected().unwrap()]
                             .try_number as usize
-                                > self.table_dag_runs.try_number
-                        {
-                            self.table_dag_runs.try_number += 1;
-                            log::info!("{}", format!("{}", self.table_dag_runs.try_number));
-                            let log = self.table_dag_runs.tasks.as_mut().unwrap().task_instances
-                                [self.table_dag_runs.table_tasks_state.selected().unwrap()]
-                            .get_logs(
-                                &self.client,
-                                &self.config.airflow,
-                                &self.config.airflow.username,
-                                &self.config.airflow.password,
-                                &self.config.airflow.host,
-                                self.table_dag_runs.try_number,
-                            )
-                            .await?;
-                            self.table_dag_runs.log = log;
-                        }
+                                > self.table_dag_runs.try_number =>
+                    {
+                        worker.unfollow_log();
+                        self.table_dag_runs.following_log = false;
+                        self.table_dag_runs.try_number += 1;
+                        log::info!("{}", self.table_dag_runs.try_number);
+                        self.load_log(&worker);
                     }
-                    Action::PreviousTryNumber => {
+                    Action::PreviousTryNumber
                         if self.table_dag_runs.table_tasks_state.selected().is_some()
                             && self.observable_mode.get() == Mode::Log
-                            && self.table_dag_runs.try_number > 1
-                        {
-                            self.table_dag_runs.try_number -= 1;
-                            log::info!("{}", format!("{}", self.table_dag_runs.try_number));
-                            let log = self.table_dag_runs.tasks.as_mut().unwrap().task_instances
-                                [self.table_dag_runs.table_tasks_state.selected().unwrap()]
-                            .get_logs(
-                                &self.client,
-                                &self.config.airflow,
-                                &self.config.airflow.username,
-                                &self.config.airflow.password,
-                                &self.config.airflow.host,
-                                self.table_dag_runs.try_number,
-                            )
-                            .await?;
-                            self.table_dag_runs.log = log;
+                            && self.table_dag_runs.try_number > 1 =>
+                    {
+                        worker.unfollow_log();
+                        self.table_dag_runs.following_log = false;
+                        self.table_dag_runs.try_number -= 1;
+                        log::info!("{}", self.table_dag_runs.try_number);
+                        self.load_log(&worker);
+                    }
+                    // A response still in flight for another task instance or try is dropped
+                    Action::LogLoaded(ref task, try_number, ref log)
+                        if !self.table_dag_runs.following_log
+                            && try_number == self.table_dag_runs.try_number
+                            && self
+                                .table_dag_runs
+                                .get_selected_task()
+                                .is_some_and(|selected| selected.is_same_instance(task)) =>
+                    {
+                        self.table_dag_runs.set_log(log.clone());
+                    }
+                    Action::FollowLog if self.observable_mode.get() == Mode::Log => {
+                        if self.table_dag_runs.following_log {
+                            worker.unfollow_log();
+                            self.table_dag_runs.following_log = false;
+                            continue;
+                        }
+                        if let Some(task) = self.table_dag_runs.get_selected_task() {
+                            // The log is fetched again from its beginning, chunk by chunk
+                            worker.follow_log(task.clone(), self.table_dag_runs.try_number);
+                            self.table_dag_runs.set_log(String::new());
+                            self.table_dag_runs.position = None;
+                            self.table_dag_runs.following_log = true;
+                        }
+                    }
+                    Action::LogAppended(ref content, end_of_log)
+                        if self.table_dag_runs.following_log =>
+                    {
+                        self.table_dag_runs.append_log(content);
+                        // The task instance will not write to the log anymore
+                        if end_of_log {
+                            worker.unfollow_log();
+                            self.table_dag_runs.following_log = false;
+                            action_tx.send(Action::Info(String::from("End of the log")))?;
                         }
                     }
+                    Action::SwitchContext(ref name) => {
+                        // The current context is kept until the new one is ready, or if it
+                        // cannot be used
+                        self.pending_context = Some(name.clone());
+                        worker.switch_context(self.config.clone(), name.clone());
+                        if self.observable_mode.get() == Mode::Command {
+                            action_tx.send(Action::DagRun)?;
+                        }
+                    }
+                    // Only the context switched to last is used
+                    Action::ContextSwitched(ref config, ref client)
+                        if self.pending_context.as_ref() == Some(&config.current_context) =>
+                    {
+                        self.pending_context = None;
+                        self.config = *config.clone();
+                        self.client = *client.clone();
+                        // Dropping the previous worker stops the polling of the previous context
+                        worker = Worker::new(
+                            self.client.clone(),
+                            self.config.get_page_size(),
+                            action_tx.clone(),
+                        );
+                        worker.watch_dag_runs(None);
+                        worker.watch_dags();
+                        worker.detect_version();
+                        self.dag_runs = DagRuns::new();
+                        self.table_dag_runs.dags = Dags::new();
+                        self.loading_dag_runs_page = false;
+                        self.table_dag_runs.set_dag_runs(DagRuns::new());
+                        self.table_dag_runs.tasks = None;
+                        self.table_dag_runs.table_state.select(None);
+                        self.table_dag_runs.user_search = None;
+                        self.table_dag_runs.dag_id = None;
+                        self.context_information
+                            .register_config_handler(self.config.clone())?;
+                        self.shortcut.register_config_handler(self.config.clone())?;
+                        self.ascii.register_config_handler(self.config.clone())?;
+                        self.table_dag_runs
+                            .register_config_handler(self.config.clone())?;
+                        action_tx.send(Action::DagRun)?;
+                    }
+                    Action::TogglePause => match self.get_selected_dag_id() {
+                        Some(dag_id) => worker.toggle_dag_paused(dag_id),
+                        None => action_tx.send(Action::Error(String::from("No dag selected")))?,
+                    },
+                    Action::SetDagPaused(ref dag_id, is_paused) => {
+                        worker.set_dag_paused(dag_id.clone(), is_paused);
+                    }
+                    Action::DagsLoaded(ref dags) => {
+                        self.table_dag_runs.dags = dags.clone();
+                    }
+                    Action::VersionDetected(ref version) => {
+                        self.context_information.set_airflow_version(version);
+                    }
+                    Action::DagUpdated(ref dag) => {
+                        self.table_dag_runs.dags.update_dag(*dag.clone());
+                    }
+                    Action::Confirm(ref message, ref action) => {
+                        self.mode_before_confirm = self.observable_mode.get();
+                        self.confirm.ask(message.clone(), *action.clone());
+                        self.observable_mode.set_mode(Mode::Confirm);
+                        self.status_bar.register_mode(self.observable_mode.get());
+                    }
+                    Action::Accept | Action::Cancel => {
+                        self.clear_confirm.close();
+                        self.mark_confirm.close();
+                        self.observable_mode.set_mode(self.mode_before_confirm);
+                        self.status_bar.register_mode(self.observable_mode.get());
+                        if let Some(confirmed_action) = self.confirm.take_action() {
+                            if action == Action::Accept {
+                                action_tx.send(confirmed_action)?;
+                            }
+                        }
+                    }
+                    Action::Trigger => match self.get_selected_dag_id() {
+                        Some(dag_id) => action_tx.send(Action::TriggerDag(dag_id))?,
+                        None => action_tx.send(Action::Error(String::from("No dag selected")))?,
+                    },
+                    Action::TriggerDag(ref dag_id) => {
+                        // The editor takes over the terminal while the trigger is edited
+                        tui.exit()?;
+                        let edited = edit_in_editor(".json5", &DagRunTrigger::template(dag_id));
+                        tui.enter()?;
+                        tui.clear()?;
+                        // Nothing is triggered if the template was left unchanged
+                        let trigger = edited.and_then(|content| match content {
+                            Some(content) => DagRunTrigger::parse(&content),
+                            None => Ok(None),
+                        });
+                        match trigger {
+                            Ok(Some(trigger)) => worker.trigger_dag_run(dag_id.clone(), trigger),
+                            Ok(None) => {}
+                            Err(e) => {
+                                action_tx.send(Action::Error(format!("Invalid trigger: {}", e)))?
+                            }
+                        }
+                        if self.observable_mode.get() == Mode::Command {
+                            action_tx.send(Action::DagRun)?;
+                        }
+                    }
+                    // The runs of another dag are not listed
+                    Action::DagRunTriggered(ref dag_run)
+                        if worker.get_watched_dag().unwrap_or(&dag_run.dag_id)
+                            == dag_run.dag_id =>
+                    {
+                        self.dag_runs.insert_dag_run(*dag_run.clone());
+                        self.table_dag_runs.set_dag_runs(self.dag_runs.clone());
+                    }
+                    // A response still in flight for the previously watched dag is dropped
+                    Action::DagRunsLoaded(ref dag_id, ref dag_runs)
+                        if worker.get_watched_dag() == dag_id.as_deref() =>
+                    {
+                        self.dag_runs.merge_first_page(dag_runs.clone());
+                        self.table_dag_runs.set_dag_runs(self.dag_runs.clone());
+                    }
+                    Action::LoadDagRunsPage if !self.loading_dag_runs_page => {
+                        self.loading_dag_runs_page = true;
+                        worker.load_dag_runs_page(self.dag_runs.get_loaded_entries());
+                    }
+                    Action::DagRunsPageLoaded(ref dag_id, ref dag_runs)
+                        if worker.get_watched_dag() == dag_id.as_deref() =>
+                    {
+                        self.loading_dag_runs_page = false;
+                        self.dag_runs.append_page(dag_runs.clone());
+                        self.table_dag_runs.set_dag_runs(self.dag_runs.clone());
+                    }
+                    // The page is fetched again once the user scrolls past the end again
+                    Action::DagRunsPageFailed(ref message) => {
+                        self.loading_dag_runs_page = false;
+                        action_tx.send(Action::Error(message.clone()))?;
+                    }
+                    // A response still in flight for the previously watched dag run is dropped
+                    Action::TasksLoaded(ref dag_id, ref dag_run_id, ref tasks)
+                        if matches!(self.observable_mode.get(), Mode::Task | Mode::Log)
+                            && worker.is_watching_tasks(dag_id, dag_run_id) =>
+                    {
+                        self.table_dag_runs.tasks = Some(tasks.clone());
+                    }
                     Action::ClearSearch => {
                         self.table_dag_runs.user_search = None;
+                        // Leaving the runs of a single dag lists the runs of every dag again
+                        if self.observable_mode.get() == Mode::DagRun
+                            && worker.get_watched_dag().is_some()
+                        {
+                            action_tx.send(Action::Runs(None))?;
+                        }
+                    }
+                    Action::Runs(ref dag_id) => {
+                        // The runs of a single dag are fetched on their own, the pages loaded
+                        // hold the most recent runs of every dag
+                        if worker.get_watched_dag() != dag_id.as_deref() {
+                            worker.watch_dag_runs(dag_id.clone());
+                            self.dag_runs = DagRuns::new();
+                            self.loading_dag_runs_page = false;
+                            self.table_dag_runs.set_dag_runs(DagRuns::new());
+                        }
+                        self.table_dag_runs.dag_id = dag_id.clone();
+                        self.table_dag_runs.user_search = None;
+                        self.table_dag_runs.table_state.select(None);
+                        action_tx.send(Action::DagRun)?;
+                    }
+                    Action::Dag => {
+                        worker.unwatch_tasks();
+                        self.status_bar.mode_breadcrumb.clear();
+                        self.observable_mode.set_mode(Mode::Dag);
+                        self.status_bar.register_mode(self.observable_mode.get());
+                    }
+                    Action::OpenDag => match self.table_dag_runs.get_selected_dag() {
+                        Some(dag) => action_tx.send(Action::Runs(Some(dag.dag_id.clone())))?,
+                        None => action_tx.send(Action::Error(String::from("No dag selected")))?,
+                    },
+                    Action::Pool => {
+                        worker.unwatch_tasks();
+                        self.status_bar.mode_breadcrumb.clear();
+                        self.observable_mode.set_mode(Mode::Pool);
+                        self.status_bar.register_mode(self.observable_mode.get());
+                        self.table_dag_runs.table_pools_state.select(None);
+                        worker.load_pools();
+                    }
+                    Action::PoolsLoaded(ref pools) => {
+                        self.table_dag_runs.pools = pools.clone();
+                    }
+                    // A command that failed to parse leaves the command bar
+                    Action::CommandFailed(ref message) => {
+                        action_tx.send(Action::Error(message.clone()))?;
+                        action_tx.send(Action::DagRun)?;
                     }
                     _ => {}
                 }
+                // A followed log is no longer fetched once the user leaves it
+                if self.table_dag_runs.following_log
+                    && !matches!(
+                        self.observable_mode.get(),
+                        Mode::Log | Mode::TextSearch | Mode::Confirm
+                    )
+                {
+                    worker.unfollow_log();
+                    self.table_dag_runs.following_log = false;
+                }
+                if let Some(action) = self.status_bar.update(action.clone())? {
+                    action_tx.send(action)?
+                };
+
                 if let Some(action) = self.context_information.update(action.clone())? {
                     action_tx.send(action)?
                 };
@@ -517,6 +911,21 @@ impl App {
                 };
                 self.command.handle_mode(self.observable_mode.get())?;
 
+                if let Some(action) = self.confirm.update(action.clone())? {
+                    action_tx.send(action)?
+                };
+                self.confirm.handle_mode(self.observable_mode.get())?;
+
+                if let Some(action) = self.clear_confirm.update(action.clone())? {
+                    action_tx.send(action)?
+                };
+                self.clear_confirm.handle_mode(self.observable_mode.get())?;
+
+                if let Some(action) = self.mark_confirm.update(action.clone())? {
+                    action_tx.send(action)?
+                };
+                self.mark_confirm.handle_mode(self.observable_mode.get())?;
+
                 if let Some(action) = self.table_dag_runs.update(action.clone())? {
                     action_tx.send(action)?
                 };
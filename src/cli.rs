@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use clap::Parser;
+use clap::{Parser, Subcommand, ValueEnum};
 
 use crate::utils::version;
 
@@ -24,4 +24,131 @@ pub struct Cli {
         default_value_t = 4.0
     )]
     pub frame_rate: f64,
+
+    #[arg(
+        short,
+        long,
+        value_name = "NAME",
+        help = "Airflow context to use, as named in the configuration file"
+    )]
+    pub context: Option<String>,
+
+    #[arg(
+        short,
+        long,
+        value_enum,
+        global = true,
+        help = "Output format of the subcommands",
+        default_value_t = OutputFormat::Table
+    )]
+    pub output: OutputFormat,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Subcommands printing their result instead of launching the TUI, for scripts.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// List the most recent dag runs
+    Runs {
+        #[arg(
+            short,
+            long,
+            value_name = "DAG_ID",
+            help = "Only list the runs of this dag"
+        )]
+        dag: Option<String>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "STATE",
+            help = "Only list the runs in this state, can be repeated"
+        )]
+        state: Vec<String>,
+
+        #[arg(
+            short,
+            long,
+            value_name = "INT",
+            help = "Maximum number of runs, the configured page size by default"
+        )]
+        limit: Option<u32>,
+    },
+    /// List the task instances of a dag run
+    Tasks { dag_id: String, run_id: String },
+    /// Print the log of a task instance
+    Logs {
+        dag_id: String,
+        run_id: String,
+        task_id: String,
+
+        #[arg(
+            long = "try",
+            value_name = "INT",
+            help = "Try number of the log, the last try by default"
+        )]
+        try_number: Option<usize>,
+    },
+    /// Clear the task instances of a dag run, only listing them without --yes
+    Clear {
+        dag_id: String,
+        run_id: String,
+
+        #[arg(
+            short,
+            long = "task",
+            value_name = "TASK_ID",
+            help = "Only clear this task, can be repeated"
+        )]
+        tasks: Vec<String>,
+
+        #[arg(long, help = "Only clear the failed task instances")]
+        only_failed: bool,
+
+        #[arg(long, help = "Also clear the upstream tasks")]
+        upstream: bool,
+
+        #[arg(long, help = "Also clear the downstream tasks")]
+        downstream: bool,
+
+        #[arg(long, help = "Also clear the runs after this one")]
+        future: bool,
+
+        #[arg(long, help = "Also clear the runs before this one")]
+        past: bool,
+
+        #[arg(
+            short,
+            long,
+            help = "Clear for real instead of listing the task instances"
+        )]
+        yes: bool,
+    },
+    /// Trigger a new dag run
+    Trigger {
+        dag_id: String,
+
+        #[arg(
+            long,
+            value_name = "JSON",
+            help = "Configuration of the run, a JSON object"
+        )]
+        conf: Option<String>,
+
+        #[arg(long, value_name = "DATE", help = "Logical date of the run")]
+        logical_date: Option<String>,
+
+        #[arg(long, value_name = "TEXT", help = "Note displayed on the run")]
+        note: Option<String>,
+    },
+    /// Check the configuration files, exiting with an error if any is invalid
+    CheckConfig,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    Table,
+    Json,
 }
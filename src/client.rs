@@ -0,0 +1,512 @@
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{eyre, Result};
+use reqwest::header::ACCEPT;
+use reqwest::{Certificate, Client, Identity, Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::{Mutex, OnceCell};
+
+use crate::config::{Airflow, Auth, Tls};
+use crate::models::clear::{ClearRequest, TaskInstanceReference, TaskInstanceReferences};
+use crate::models::dag::Dag;
+use crate::models::dag_run::{DagRun, DagRunTrigger};
+use crate::models::dag_runs::DagRuns;
+use crate::models::dags::Dags;
+use crate::models::log::Log;
+use crate::models::mark::MarkRequest;
+use crate::models::pools::Pools;
+use crate::models::task::Task;
+use crate::models::tasks::Tasks;
+use crate::models::version::{AirflowVersion, VersionInfo, DATA_INTERVALS, NOTES};
+
+// Number of dags fetched per request, Airflow's default `maximum_page_limit`
+const DAGS_PAGE_SIZE: u32 = 100;
+// Number of task instances fetched by request, a run of a mapped task may hold thousands
+const TASK_INSTANCES_PAGE_SIZE: u32 = 100;
+// Maximum time given to the webserver to accept a connection
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+// Maximum time given to the webserver to answer a request of the API
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+// Maximum time given to the webserver to send a whole log, which may be read from a remote
+// storage and weigh megabytes
+pub const LOG_TIMEOUT: Duration = Duration::from_secs(120);
+// Time a token printed by a command is used for before running the command again
+const COMMAND_TOKEN_LIFETIME: Duration = Duration::from_secs(5 * 60);
+
+/// Error answered by the Airflow REST API, with the detail of its problem+json body if any.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AirflowError {
+    Unauthorized(String),
+    Forbidden(String),
+    NotFound(String),
+    Server(StatusCode, String),
+    Other(StatusCode, String),
+}
+
+impl AirflowError {
+    fn new(status: StatusCode, body: &str) -> Self {
+        let detail = Problem::detail(body).unwrap_or_else(|| {
+            status
+                .canonical_reason()
+                .unwrap_or("No detail given")
+                .to_string()
+        });
+        match status {
+            StatusCode::UNAUTHORIZED => AirflowError::Unauthorized(detail),
+            StatusCode::FORBIDDEN => AirflowError::Forbidden(detail),
+            StatusCode::NOT_FOUND => AirflowError::NotFound(detail),
+            status if status.is_server_error() => AirflowError::Server(status, detail),
+            status => AirflowError::Other(status, detail),
+        }
+    }
+}
+
+impl fmt::Display for AirflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AirflowError::Unauthorized(detail) => write!(
+                f,
+                "Authentication failed, check the credentials of the context ({})",
+                detail
+            ),
+            AirflowError::Forbidden(detail) => write!(f, "Permission denied ({})", detail),
+            AirflowError::NotFound(detail) => write!(f, "Not found ({})", detail),
+            AirflowError::Server(status, detail) => {
+                write!(
+                    f,
+                    "Airflow webserver error {} ({})",
+                    status.as_u16(),
+                    detail
+                )
+            }
+            AirflowError::Other(status, detail) => {
+                write!(
+                    f,
+                    "Airflow refused the request {} ({})",
+                    status.as_u16(),
+                    detail
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for AirflowError {}
+
+/// Body of an Airflow error, as described by RFC 7807.
+#[derive(Deserialize, Debug, Default)]
+struct Problem {
+    title: Option<String>,
+    detail: Option<String>,
+}
+
+impl Problem {
+    /// The most precise description found in `body`, the raw body if it is not problem+json.
+    fn detail(body: &str) -> Option<String> {
+        match serde_json::from_str::<Problem>(body) {
+            Ok(problem) => problem.detail.or(problem.title),
+            Err(_) => Some(body.trim())
+                .filter(|body| !body.is_empty())
+                .map(String::from),
+        }
+    }
+}
+
+/// Token printed by the command of an `Auth::Command`, with the time it was printed at.
+#[derive(Debug)]
+struct CommandToken {
+    token: String,
+    printed_at: Instant,
+}
+
+/// Client of the Airflow REST API of a context, every endpoint used by arvz goes through it.
+#[derive(Debug, Clone)]
+pub struct AirflowClient {
+    http: Client,
+    host: String,
+    airflow: Airflow,
+    // Shared by the clones so that the command runs once for every request
+    command_token: Arc<Mutex<Option<CommandToken>>>,
+    // Asked once to the webserver, the requests are adapted to the older versions
+    version: Arc<OnceCell<AirflowVersion>>,
+}
+
+/// Clients are equal when they send the same requests to the same webserver, whatever the
+/// state of their connections.
+impl PartialEq for AirflowClient {
+    fn eq(&self, other: &Self) -> bool {
+        self.airflow == other.airflow
+    }
+}
+
+impl AirflowClient {
+    /// Client of `airflow`, its clones share the same connections.
+    pub fn new(airflow: &Airflow) -> Result<Self> {
+        Ok(Self {
+            http: build_http_client(&airflow.tls)?,
+            host: airflow.host.trim_end_matches('/').to_string(),
+            airflow: airflow.clone(),
+            command_token: Arc::default(),
+            version: Arc::default(),
+        })
+    }
+
+    /// Version of the Airflow webserver, fetched on the first call only.
+    pub async fn get_version(&self) -> Result<AirflowVersion> {
+        let version = self
+            .version
+            .get_or_try_init(|| async {
+                let info: VersionInfo = self.get_json("version", &[]).await?;
+                info.version.parse::<AirflowVersion>()
+            })
+            .await?;
+        Ok(*version)
+    }
+
+    /// Fetch every active dag, page by page.
+    pub async fn get_dags(&self) -> Result<Dags> {
+        let mut dags = Dags::new();
+        loop {
+            let page: Dags = self
+                .get_json(
+                    "dags",
+                    &[
+                        ("only_active", String::from("true")),
+                        ("limit", DAGS_PAGE_SIZE.to_string()),
+                        ("offset", dags.dags.len().to_string()),
+                    ],
+                )
+                .await?;
+            let is_last_page = page.dags.is_empty();
+            dags.dags.extend(page.dags);
+            dags.total_entries = page.total_entries;
+            if is_last_page || dags.dags.len() as u32 >= dags.total_entries {
+                return Ok(dags);
+            }
+        }
+    }
+
+    /// Fetch `dag_id` alone.
+    pub async fn get_dag(&self, dag_id: &str) -> Result<Dag> {
+        self.get_json(&format!("dags/{}", dag_id), &[]).await
+    }
+
+    /// Pause or unpause `dag_id` and return the updated dag.
+    pub async fn set_dag_paused(&self, dag_id: &str, is_paused: bool) -> Result<Dag> {
+        let request = self
+            .request(Method::PATCH, &format!("dags/{}", dag_id))
+            .await?
+            .query(&[("update_mask", "is_paused")])
+            .json(&json!({ "is_paused": is_paused }));
+        Ok(self.send(request).await?.json::<Dag>().await?)
+    }
+
+    /// Fetch the source code of the file defining `dag_id`.
+    pub async fn get_dag_source(&self, dag_id: &str) -> Result<String> {
+        let dag: Dag = self
+            .get_json(&format!("dags/{}/details", dag_id), &[])
+            .await?;
+        let request = self
+            .request(Method::GET, &format!("dagSources/{}", dag.file_token))
+            .await?;
+        Ok(self.send(request).await?.text().await?)
+    }
+
+    /// Fetch `limit` dag runs from `offset`, the most recent first, of `dag_id` or of every dag,
+    /// in one of `states` if any.
+    pub async fn get_dag_runs(
+        &self,
+        dag_id: Option<&str>,
+        states: &[String],
+        limit: u32,
+        offset: u32,
+    ) -> Result<DagRuns> {
+        let mut query = vec![
+            ("order_by", String::from("-start_date")),
+            ("limit", limit.to_string()),
+            ("offset", offset.to_string()),
+        ];
+        query.extend(states.iter().map(|state| ("state", state.clone())));
+        self.get_json(&format!("dags/{}/dagRuns", dag_id.unwrap_or("~")), &query)
+            .await
+    }
+
+    /// Trigger a new run of `dag_id` and return it. The logical date is sent as the execution
+    /// date to the webservers older than 2.2.
+    pub async fn trigger_dag_run(&self, dag_id: &str, trigger: &DagRunTrigger) -> Result<DagRun> {
+        let mut body = serde_json::to_value(trigger)?;
+        // The body is sent as is when the version cannot be read
+        if let Ok(version) = self.get_version().await {
+            if version < NOTES && trigger.note.is_some() {
+                return Err(eyre!(
+                    "Notes on dag runs require Airflow {}, the webserver runs {}",
+                    NOTES,
+                    version
+                ));
+            }
+            if version < DATA_INTERVALS {
+                if let Some(logical_date) = body
+                    .as_object_mut()
+                    .and_then(|body| body.remove("logical_date"))
+                {
+                    body["execution_date"] = logical_date;
+                }
+            }
+        }
+        let request = self
+            .request(Method::POST, &format!("dags/{}/dagRuns", dag_id))
+            .await?
+            .json(&body);
+        Ok(self.send(request).await?.json::<DagRun>().await?)
+    }
+
+    /// Fetch every task instance of the run `dag_run_id` of `dag_id`, page by page.
+    pub async fn get_task_instances(&self, dag_id: &str, dag_run_id: &str) -> Result<Tasks> {
+        let path = format!("dags/{}/dagRuns/{}/taskInstances", dag_id, dag_run_id);
+        let mut tasks = Tasks::new()?;
+        // The offset moves by whole pages, as the task instances of an unexpected shape are
+        // dropped from the pages read
+        let mut offset = 0;
+        loop {
+            let page: Tasks = self
+                .get_json(
+                    &path,
+                    &[
+                        ("limit", TASK_INSTANCES_PAGE_SIZE.to_string()),
+                        ("offset", offset.to_string()),
+                    ],
+                )
+                .await?;
+            offset += TASK_INSTANCES_PAGE_SIZE;
+            let is_last_page = page.task_instances.is_empty();
+            tasks.task_instances.extend(page.task_instances);
+            tasks.total_entries = page.total_entries;
+            if is_last_page || offset >= tasks.total_entries {
+                return Ok(tasks);
+            }
+        }
+    }
+
+    /// Fetch the whole log of `try_number` of `task`.
+    pub async fn get_log(&self, task: &Task, try_number: usize) -> Result<String> {
+        let request = self
+            .log_request(task, try_number)
+            .await?
+            .timeout(LOG_TIMEOUT);
+        Ok(self.send(request).await?.text().await?)
+    }
+
+    /// Fetch the log of `try_number` of `task` written after `token`, from the beginning
+    /// without token.
+    pub async fn get_log_chunk(
+        &self,
+        task: &Task,
+        try_number: usize,
+        token: Option<&str>,
+    ) -> Result<Log> {
+        let mut request = self
+            .log_request(task, try_number)
+            .await?
+            .header(ACCEPT, "application/json");
+        if let Some(token) = token {
+            request = request.query(&[("token", token)]);
+        }
+        Ok(self.send(request).await?.json::<Log>().await?)
+    }
+
+    /// Clear the task instances targeted by `clear`, or only list the ones that would be
+    /// cleared if `dry_run`.
+    pub async fn clear_task_instances(
+        &self,
+        clear: &ClearRequest,
+        dry_run: bool,
+    ) -> Result<Vec<TaskInstanceReference>> {
+        let mut body = serde_json::to_value(clear)?;
+        body["dry_run"] = json!(dry_run);
+        body["reset_dag_runs"] = json!(true);
+        body["include_subdags"] = json!(true);
+        body["include_parentdag"] = json!(true);
+        let request = self
+            .request(
+                Method::POST,
+                &format!("dags/{}/clearTaskInstances", clear.dag_id),
+            )
+            .await?
+            .json(&body);
+        Ok(self
+            .send(request)
+            .await?
+            .json::<TaskInstanceReferences>()
+            .await?
+            .task_instances)
+    }
+
+    /// Mark the dag run or the task instances targeted by `mark`.
+    pub async fn mark(&self, mark: &MarkRequest) -> Result<()> {
+        let request = match &mark.task_id {
+            None => self
+                .request(
+                    Method::PATCH,
+                    &format!("dags/{}/dagRuns/{}", mark.dag_id, mark.dag_run_id),
+                )
+                .await?
+                .json(&json!({ "state": mark.state })),
+            Some(task_id) => self
+                .request(
+                    Method::POST,
+                    &format!("dags/{}/updateTaskInstancesState", mark.dag_id),
+                )
+                .await?
+                .json(&json!({
+                    "dry_run": false,
+                    "dag_run_id": mark.dag_run_id,
+                    "task_id": task_id,
+                    "new_state": mark.state,
+                    "include_upstream": mark.include_upstream,
+                    "include_downstream": mark.include_downstream,
+                    "include_future": false,
+                    "include_past": false,
+                })),
+        };
+        self.send(request).await?;
+        Ok(())
+    }
+
+    pub async fn get_pools(&self) -> Result<Pools> {
+        self.get_json("pools", &[]).await
+    }
+
+    /// Request on `path`, relative to the root of the API, authenticated as configured in the
+    /// context and bounded by `REQUEST_TIMEOUT`.
+    async fn request(&self, method: Method, path: &str) -> Result<RequestBuilder> {
+        let mut request = self
+            .http
+            .request(method, format!("{}/api/v1/{}", self.host, path))
+            .timeout(REQUEST_TIMEOUT);
+        for (name, value) in &self.airflow.headers {
+            request = request.header(name, value);
+        }
+        Ok(match &self.airflow.auth {
+            Auth::Basic => {
+                request.basic_auth(&self.airflow.username, Some(self.airflow.password.expose()))
+            }
+            Auth::Bearer { token } => request.bearer_auth(token.expose()),
+            Auth::Command { command } => {
+                request.bearer_auth(self.get_command_token(command).await?)
+            }
+            Auth::None => request,
+        })
+    }
+
+    /// The token printed by `command`, run again once the previous token is too old.
+    async fn get_command_token(&self, command: &str) -> Result<String> {
+        let mut command_token = self.command_token.lock().await;
+        if let Some(CommandToken { token, printed_at }) = command_token.as_ref() {
+            if printed_at.elapsed() < COMMAND_TOKEN_LIFETIME {
+                return Ok(token.clone());
+            }
+        }
+        let output = tokio::process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .output()
+            .await?;
+        if !output.status.success() {
+            return Err(eyre!(
+                "Token command `{}` failed: {}",
+                command,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        let token = String::from_utf8(output.stdout)?.trim().to_string();
+        if token.is_empty() {
+            return Err(eyre!("Token command `{}` printed no token", command));
+        }
+        *command_token = Some(CommandToken {
+            token: token.clone(),
+            printed_at: Instant::now(),
+        });
+        Ok(token)
+    }
+
+    /// Send `request`, an error status is turned into an `AirflowError` before any decoding.
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let res = request.send().await?;
+        let status = res.status();
+        if status.is_success() {
+            return Ok(res);
+        }
+        // A rejected token is expired or revoked, the command prints a new one next time
+        if status == StatusCode::UNAUTHORIZED {
+            *self.command_token.lock().await = None;
+        }
+        let body = res.text().await.unwrap_or_default();
+        Err(AirflowError::new(status, &body).into())
+    }
+
+    /// Request on the log of `try_number` of `task`, the instances of a mapped task sharing
+    /// the same path.
+    async fn log_request(&self, task: &Task, try_number: usize) -> Result<RequestBuilder> {
+        let path = format!(
+            "dags/{}/dagRuns/{}/taskInstances/{}/logs/{}",
+            task.dag_id, task.dag_run_id, task.task_id, try_number
+        );
+        let mut request = self.request(Method::GET, &path).await?;
+        if let Some(map_index) = task.get_map_index() {
+            request = request.query(&[("map_index", map_index)]);
+        }
+        Ok(request)
+    }
+
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T> {
+        let request = self.request(Method::GET, path).await?.query(query);
+        Ok(self.send(request).await?.json::<T>().await?)
+    }
+}
+
+/// HTTP client trusting the CA and presenting the certificate configured in `tls`.
+/// Connections are bounded by `CONNECT_TIMEOUT`, each request sets its own timeout.
+fn build_http_client(tls: &Tls) -> Result<Client> {
+    let mut builder = Client::builder()
+        .use_rustls_tls()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .danger_accept_invalid_certs(tls.insecure_skip_verify);
+    if let Some(ca_file) = &tls.ca_file {
+        let pem = read_pem(ca_file)?;
+        for certificate in Certificate::from_pem_bundle(&pem)? {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    match (&tls.client_cert, &tls.client_key) {
+        (Some(client_cert), Some(client_key)) => {
+            // The identity is read from a single PEM holding both the key and the certificate
+            let mut pem = read_pem(client_key)?;
+            pem.push(b'\n');
+            pem.extend(read_pem(client_cert)?);
+            builder = builder.identity(Identity::from_pem(&pem)?);
+        }
+        (None, None) => {}
+        _ => {
+            return Err(eyre!(
+                "tls.client_cert and tls.client_key must be set together"
+            ))
+        }
+    }
+    Ok(builder.build()?)
+}
+
+fn read_pem(path: &Path) -> Result<Vec<u8>> {
+    std::fs::read(path).map_err(|e| eyre!("Cannot read {}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests;
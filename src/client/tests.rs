@@ -0,0 +1,646 @@
+use wiremock::matchers::{
+    any, basic_auth, bearer_token, body_partial_json, header, method, path, query_param,
+    query_param_is_missing,
+};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use super::*;
+use crate::config::Secret;
+use crate::models::clear::ClearOptions;
+use crate::models::mark::MarkState;
+use crate::test_utils::fixture;
+
+const USERNAME: &str = "admin";
+const PASSWORD: &str = "admin";
+const DAG_ID: &str = "example_bash_operator";
+const RUN_ID: &str = "scheduled__2024-03-11T00:00:00+00:00";
+// Below the default priority of wiremock
+const REPLAY_PRIORITY: u8 = 10;
+
+fn reply(name: &str) -> ResponseTemplate {
+    let content_type = match Path::new(name).extension().and_then(|e| e.to_str()) {
+        Some("json") => "application/json",
+        _ => "text/plain",
+    };
+    ResponseTemplate::new(200).set_body_raw(fixture(name), content_type)
+}
+
+fn problem(status: u16, name: &str) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_raw(fixture(name), "application/problem+json")
+}
+
+/// Stub webserver replaying the recorded responses of the read endpoints to authenticated
+/// requests. Any other request is rejected as Airflow does without valid credentials.
+/// The mocks mounted by a test take precedence over the replayed responses.
+async fn stub_airflow() -> MockServer {
+    let server = MockServer::start().await;
+    let file_token =
+        "Ii9vcHQvYWlyZmxvdy9kYWdzL2V4YW1wbGVfYmFzaF9vcGVyYXRvci5weSI.2sRcvBqHmkWJ2VXhpEnJ-GsrcL0";
+    let routes = [
+        ("/api/v1/version".to_string(), "version.json"),
+        ("/api/v1/dags".to_string(), "dags.json"),
+        (
+            format!("/api/v1/dags/{}/details", DAG_ID),
+            "dag_details.json",
+        ),
+        (
+            format!("/api/v1/dagSources/{}", file_token),
+            "dag_source.py",
+        ),
+        ("/api/v1/dags/~/dagRuns".to_string(), "dag_runs.json"),
+        (format!("/api/v1/dags/{}/dagRuns", DAG_ID), "dag_runs.json"),
+        (
+            format!("/api/v1/dags/{}/dagRuns/{}/taskInstances", DAG_ID, RUN_ID),
+            "task_instances.json",
+        ),
+        ("/api/v1/pools".to_string(), "pools.json"),
+    ];
+    for (route, name) in routes {
+        Mock::given(method("GET"))
+            .and(path(route))
+            .and(basic_auth(USERNAME, PASSWORD))
+            .respond_with(reply(name))
+            .with_priority(REPLAY_PRIORITY)
+            .mount(&server)
+            .await;
+    }
+    Mock::given(any())
+        .respond_with(problem(401, "unauthorized.json"))
+        .with_priority(u8::MAX)
+        .mount(&server)
+        .await;
+    server
+}
+
+fn airflow(server: &MockServer) -> Airflow {
+    Airflow {
+        host: server.uri(),
+        username: USERNAME.to_string(),
+        password: Secret::Plain(PASSWORD.to_string()),
+        ..Airflow::default()
+    }
+}
+
+fn client(server: &MockServer) -> AirflowClient {
+    AirflowClient::new(&airflow(server)).unwrap()
+}
+
+fn airflow_error(report: color_eyre::eyre::Report) -> AirflowError {
+    report
+        .downcast_ref::<AirflowError>()
+        .cloned()
+        .unwrap_or_else(|| panic!("not an Airflow error: {}", report))
+}
+
+#[tokio::test]
+async fn get_dags() {
+    let server = stub_airflow().await;
+    let dags = client(&server).get_dags().await.unwrap();
+    assert_eq!(dags.get_total_entries(), 2);
+    assert!(!dags.is_paused(DAG_ID));
+    assert!(dags.is_paused("example_python_operator"));
+}
+
+#[tokio::test]
+async fn get_dags_reads_every_page() {
+    let server = MockServer::start().await;
+    let mut first_page: serde_json::Value = serde_json::from_str(&fixture("dags.json")).unwrap();
+    first_page["total_entries"] = json!(3);
+    let mut last_page = first_page.clone();
+    last_page["dags"].as_array_mut().unwrap().truncate(1);
+    Mock::given(path("/api/v1/dags"))
+        .and(query_param("offset", "0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(first_page))
+        .mount(&server)
+        .await;
+    Mock::given(path("/api/v1/dags"))
+        .and(query_param("offset", "2"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(last_page))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dags = client(&server).get_dags().await.unwrap();
+    assert_eq!(dags.dags.len(), 3);
+}
+
+#[tokio::test]
+async fn get_dag() {
+    let server = stub_airflow().await;
+    Mock::given(method("GET"))
+        .and(path(format!("/api/v1/dags/{}", DAG_ID)))
+        .respond_with(reply("dag.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dag = client(&server).get_dag(DAG_ID).await.unwrap();
+    assert_eq!(dag.dag_id, DAG_ID);
+    assert!(dag.is_paused());
+}
+
+#[tokio::test]
+async fn set_dag_paused() {
+    let server = stub_airflow().await;
+    Mock::given(method("PATCH"))
+        .and(path(format!("/api/v1/dags/{}", DAG_ID)))
+        .and(query_param("update_mask", "is_paused"))
+        .and(body_partial_json(json!({ "is_paused": true })))
+        .respond_with(reply("dag.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let dag = client(&server).set_dag_paused(DAG_ID, true).await.unwrap();
+    assert_eq!(dag.dag_id, DAG_ID);
+    assert!(dag.is_paused());
+}
+
+#[tokio::test]
+async fn get_dag_source() {
+    let server = stub_airflow().await;
+    let source = client(&server).get_dag_source(DAG_ID).await.unwrap();
+    assert!(source.contains("dag_id=\"example_bash_operator\""));
+}
+
+#[tokio::test]
+async fn get_dag_runs() {
+    let server = stub_airflow().await;
+    let dag_runs = client(&server)
+        .get_dag_runs(None, &[], 100, 0)
+        .await
+        .unwrap();
+    assert_eq!(dag_runs.get_total_entries(), 2);
+    assert_eq!(dag_runs.get_count_dag_run_failed(), 1);
+
+    let records = dag_runs.get_records(None);
+    assert_eq!(
+        records[1].dag_run_id,
+        "manual__2024-03-10T14:22:51.052366+00:00"
+    );
+    assert_eq!(records[1].run_type, "manual");
+    assert!(records[1].external_trigger);
+}
+
+#[tokio::test]
+async fn get_dag_runs_without_data_interval() {
+    let server = stub_airflow().await;
+    Mock::given(path("/api/v1/dags/~/dagRuns"))
+        .respond_with(reply("dag_runs_2_1.json"))
+        .mount(&server)
+        .await;
+
+    let dag_runs = client(&server)
+        .get_dag_runs(None, &[], 100, 0)
+        .await
+        .unwrap();
+    let records = dag_runs.get_records(None);
+    assert_eq!(records.len(), 1);
+    assert_eq!(
+        records[0].data_interval_start.as_deref(),
+        Some("2021-06-01T00:00:00+00:00")
+    );
+    assert_eq!(
+        records[0].start_date.as_deref(),
+        Some("2021-06-02T00:00:01.173405+00:00")
+    );
+    assert_eq!(records[0].state, "success");
+}
+
+#[tokio::test]
+async fn get_dag_runs_of_a_dag_in_some_states() {
+    let server = stub_airflow().await;
+    Mock::given(method("GET"))
+        .and(path(format!("/api/v1/dags/{}/dagRuns", DAG_ID)))
+        .and(query_param("state", "failed"))
+        .and(query_param("limit", "10"))
+        .and(query_param("offset", "20"))
+        .and(query_param("order_by", "-start_date"))
+        .respond_with(reply("dag_runs.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    client(&server)
+        .get_dag_runs(Some(DAG_ID), &[String::from("failed")], 10, 20)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn trigger_dag_run() {
+    let server = stub_airflow().await;
+    Mock::given(method("POST"))
+        .and(path(format!("/api/v1/dags/{}/dagRuns", DAG_ID)))
+        .and(basic_auth(USERNAME, PASSWORD))
+        .and(body_partial_json(json!({ "conf": { "source": "arvz" } })))
+        .respond_with(reply("dag_run.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let trigger = DagRunTrigger::parse("{ conf: { source: \"arvz\" } }")
+        .unwrap()
+        .unwrap();
+    let dag_run = client(&server)
+        .trigger_dag_run(DAG_ID, &trigger)
+        .await
+        .unwrap();
+    assert_eq!(dag_run.state, "queued");
+    assert_eq!(dag_run.run_type, "manual");
+}
+
+#[tokio::test]
+async fn trigger_dag_run_on_airflow_2_1() {
+    let server = stub_airflow().await;
+    Mock::given(path("/api/v1/version"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "version": "2.1.4" })))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path(format!("/api/v1/dags/{}/dagRuns", DAG_ID)))
+        .and(body_partial_json(
+            json!({ "execution_date": "2021-06-01T00:00:00+00:00" }),
+        ))
+        .respond_with(reply("dag_run.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let trigger = DagRunTrigger {
+        logical_date: Some(String::from("2021-06-01T00:00:00+00:00")),
+        ..DagRunTrigger::default()
+    };
+    client.trigger_dag_run(DAG_ID, &trigger).await.unwrap();
+
+    let trigger = DagRunTrigger {
+        note: Some(String::from("rerun")),
+        ..DagRunTrigger::default()
+    };
+    let error = client.trigger_dag_run(DAG_ID, &trigger).await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Notes on dag runs require Airflow 2.5.0, the webserver runs 2.1.4"
+    );
+}
+
+#[tokio::test]
+async fn get_version() {
+    let server = stub_airflow().await;
+    let version = client(&server).get_version().await.unwrap();
+    assert_eq!(version, AirflowVersion::new(2, 8, 3));
+    assert_eq!(
+        "2.10.0rc1".parse::<AirflowVersion>().unwrap(),
+        AirflowVersion::new(2, 10, 0)
+    );
+    assert!("main".parse::<AirflowVersion>().is_err());
+}
+
+#[tokio::test]
+async fn get_task_instances() {
+    let server = stub_airflow().await;
+    let tasks = client(&server)
+        .get_task_instances(DAG_ID, RUN_ID)
+        .await
+        .unwrap();
+    assert_eq!(tasks.get_total_entries(), 2);
+
+    let task = tasks.get_task("run_this_last").unwrap();
+    assert_eq!(task.try_number, 2);
+    assert_eq!(task.state.as_deref(), Some("failed"));
+
+    let records = tasks.get_records();
+    assert_eq!(records[0].operator, "BashOperator");
+    assert_eq!(records[0].duration, 1.524091);
+}
+
+#[tokio::test]
+async fn get_task_instances_reads_every_page() {
+    let server = MockServer::start().await;
+    let route = format!("/api/v1/dags/{}/dagRuns/{}/taskInstances", DAG_ID, RUN_ID);
+    let mut first_page: serde_json::Value =
+        serde_json::from_str(&fixture("task_instances.json")).unwrap();
+    let task_instance = first_page["task_instances"][0].clone();
+    first_page["task_instances"] = json!(vec![task_instance; 100]);
+    first_page["total_entries"] = json!(101);
+    let mut last_page = first_page.clone();
+    last_page["task_instances"]
+        .as_array_mut()
+        .unwrap()
+        .truncate(1);
+    Mock::given(path(route.clone()))
+        .and(query_param("limit", "100"))
+        .and(query_param("offset", "0"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(first_page))
+        .mount(&server)
+        .await;
+    Mock::given(path(route))
+        .and(query_param("offset", "100"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(last_page))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let tasks = client(&server)
+        .get_task_instances(DAG_ID, RUN_ID)
+        .await
+        .unwrap();
+    assert_eq!(tasks.task_instances.len(), 101);
+}
+
+#[tokio::test]
+async fn get_task_instances_of_unexpected_shapes() {
+    let server = stub_airflow().await;
+    Mock::given(path(format!(
+        "/api/v1/dags/{}/dagRuns/{}/taskInstances",
+        DAG_ID, RUN_ID
+    )))
+    .respond_with(reply("task_instances_drift.json"))
+    .mount(&server)
+    .await;
+
+    let tasks = client(&server)
+        .get_task_instances(DAG_ID, RUN_ID)
+        .await
+        .unwrap();
+    let records = tasks.get_records();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].task_id, "runme");
+    assert_eq!(records[0].operator, "n/a");
+    assert_eq!(records[0].state, "n/a");
+    assert_eq!(records[0].try_number, 0);
+}
+
+#[tokio::test]
+async fn get_log() {
+    let server = stub_airflow().await;
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/api/v1/dags/{}/dagRuns/{}/taskInstances/run_this_last/logs/2",
+            DAG_ID, RUN_ID
+        )))
+        .and(query_param_is_missing("map_index"))
+        .and(basic_auth(USERNAME, PASSWORD))
+        .respond_with(reply("log.txt"))
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let tasks = client.get_task_instances(DAG_ID, RUN_ID).await.unwrap();
+    let task = tasks.get_task("run_this_last").unwrap();
+    let log = client.get_log(task, 2).await.unwrap();
+    assert!(log.contains("Starting attempt 2 of 2"));
+}
+
+#[tokio::test]
+async fn get_log_of_a_mapped_task() {
+    let server = stub_airflow().await;
+    let task_instances = "/api/v1/dags/example_dynamic_task_mapping/dagRuns/manual__2024-03-12T09:30:00+00:00/taskInstances";
+    Mock::given(path(task_instances))
+        .respond_with(reply("task_instances_mapped.json"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path(format!("{}/add_one/logs/2", task_instances)))
+        .and(query_param("map_index", "1"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("log of the instance 1"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let tasks = client
+        .get_task_instances(
+            "example_dynamic_task_mapping",
+            "manual__2024-03-12T09:30:00+00:00",
+        )
+        .await
+        .unwrap();
+    let task = &tasks.task_instances[1];
+    assert_eq!(task.get_map_index(), Some(1));
+    let log = client.get_log(task, 2).await.unwrap();
+    assert_eq!(log, "log of the instance 1");
+}
+
+#[tokio::test]
+async fn get_log_chunk() {
+    let server = stub_airflow().await;
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/api/v1/dags/{}/dagRuns/{}/taskInstances/run_this_last/logs/2",
+            DAG_ID, RUN_ID
+        )))
+        .and(header("accept", "application/json"))
+        .and(query_param("token", "previous"))
+        .and(basic_auth(USERNAME, PASSWORD))
+        .respond_with(reply("log_chunk.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let tasks = client.get_task_instances(DAG_ID, RUN_ID).await.unwrap();
+    let task = tasks.get_task("run_this_last").unwrap();
+    let log = client
+        .get_log_chunk(task, 2, Some("previous"))
+        .await
+        .unwrap();
+    assert_eq!(
+        log.get_text(),
+        "[2024-03-12, 00:01:03 UTC] {taskinstance.py:2193} INFO - Starting attempt 2 of 2\n"
+    );
+    assert!(log.continuation_token.is_some());
+    assert!(!log.is_end_of_log());
+}
+
+#[tokio::test]
+async fn get_last_log_chunk() {
+    let server = stub_airflow().await;
+    Mock::given(method("GET"))
+        .and(path(format!(
+            "/api/v1/dags/{}/dagRuns/{}/taskInstances/run_this_last/logs/2",
+            DAG_ID, RUN_ID
+        )))
+        .and(header("accept", "application/json"))
+        .respond_with(reply("log_chunk_end.json"))
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let tasks = client.get_task_instances(DAG_ID, RUN_ID).await.unwrap();
+    let task = tasks.get_task("run_this_last").unwrap();
+    let log = client.get_log_chunk(task, 2, None).await.unwrap();
+    assert!(log.get_text().ends_with("Task exited with return code 1\n"));
+    assert!(log.is_end_of_log());
+}
+
+#[tokio::test]
+async fn clear_task_instances() {
+    let server = stub_airflow().await;
+    Mock::given(method("POST"))
+        .and(path(format!("/api/v1/dags/{}/clearTaskInstances", DAG_ID)))
+        .and(basic_auth(USERNAME, PASSWORD))
+        .and(body_partial_json(json!({
+            "dry_run": true,
+            "dag_run_id": RUN_ID,
+            "task_ids": ["run_this_last"],
+            "only_failed": true,
+            "include_downstream": false,
+        })))
+        .respond_with(reply("clear_task_instances.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let clear = ClearRequest {
+        options: ClearOptions {
+            only_failed: true,
+            ..ClearOptions::default()
+        },
+        ..ClearRequest::task(DAG_ID, RUN_ID, "run_this_last")
+    };
+    let task_instances = client(&server)
+        .clear_task_instances(&clear, true)
+        .await
+        .unwrap();
+    assert_eq!(task_instances.len(), 1);
+    assert_eq!(task_instances[0].task_id, "run_this_last");
+}
+
+#[tokio::test]
+async fn mark_dag_run() {
+    let server = stub_airflow().await;
+    Mock::given(method("PATCH"))
+        .and(path(format!("/api/v1/dags/{}/dagRuns/{}", DAG_ID, RUN_ID)))
+        .and(basic_auth(USERNAME, PASSWORD))
+        .and(body_partial_json(json!({ "state": "failed" })))
+        .respond_with(reply("dag_run.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mark = MarkRequest::dag_run(DAG_ID, RUN_ID, MarkState::Failed);
+    client(&server).mark(&mark).await.unwrap();
+}
+
+#[tokio::test]
+async fn mark_task_instance() {
+    let server = stub_airflow().await;
+    Mock::given(method("POST"))
+        .and(path(format!(
+            "/api/v1/dags/{}/updateTaskInstancesState",
+            DAG_ID
+        )))
+        .and(basic_auth(USERNAME, PASSWORD))
+        .and(body_partial_json(json!({
+            "dry_run": false,
+            "dag_run_id": RUN_ID,
+            "task_id": "run_this_last",
+            "new_state": "success",
+            "include_downstream": true,
+        })))
+        .respond_with(reply("update_task_instances_state.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mark = MarkRequest {
+        include_downstream: true,
+        ..MarkRequest::task(DAG_ID, RUN_ID, "run_this_last", MarkState::Success)
+    };
+    client(&server).mark(&mark).await.unwrap();
+}
+
+#[tokio::test]
+async fn get_pools() {
+    let server = stub_airflow().await;
+    let pools = client(&server).get_pools().await.unwrap();
+    assert_eq!(pools.get_total_entries(), 1);
+    assert_eq!(pools.pools[0].name, "default_pool");
+    assert_eq!(pools.pools[0].open_slots, 126);
+}
+
+#[tokio::test]
+async fn bearer_token_and_headers() {
+    let server = MockServer::start().await;
+    Mock::given(path("/api/v1/pools"))
+        .and(bearer_token("t0ken"))
+        .and(header("x-proxy-user", "arvz"))
+        .respond_with(reply("pools.json"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let airflow = Airflow {
+        auth: Auth::Bearer {
+            token: Secret::Plain(String::from("t0ken")),
+        },
+        headers: [(String::from("X-Proxy-User"), String::from("arvz"))].into(),
+        ..airflow(&server)
+    };
+    AirflowClient::new(&airflow)
+        .unwrap()
+        .get_pools()
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn unauthorized() {
+    let server = stub_airflow().await;
+    let airflow = Airflow {
+        password: Secret::Plain(String::from("wrong")),
+        ..airflow(&server)
+    };
+    let report = AirflowClient::new(&airflow)
+        .unwrap()
+        .get_dag_runs(None, &[], 100, 0)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        airflow_error(report),
+        AirflowError::Unauthorized(String::from("Unauthorized"))
+    );
+}
+
+#[tokio::test]
+async fn not_found() {
+    let server = stub_airflow().await;
+    Mock::given(path(format!(
+        "/api/v1/dags/{}/dagRuns/unknown/taskInstances",
+        DAG_ID
+    )))
+    .respond_with(problem(404, "not_found.json"))
+    .mount(&server)
+    .await;
+
+    let report = client(&server)
+        .get_task_instances(DAG_ID, "unknown")
+        .await
+        .unwrap_err();
+    assert_eq!(
+        airflow_error(report),
+        AirflowError::NotFound(String::from(
+            "DAGRun with DAG ID: 'example_bash_operator' and DagRun ID: 'unknown' not found"
+        ))
+    );
+}
+
+#[tokio::test]
+async fn server_error_without_problem() {
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(502).set_body_string("upstream connect error\n"))
+        .mount(&server)
+        .await;
+
+    let report = client(&server).get_pools().await.unwrap_err();
+    assert_eq!(
+        airflow_error(report),
+        AirflowError::Server(
+            StatusCode::BAD_GATEWAY,
+            String::from("upstream connect error")
+        )
+    );
+}
@@ -0,0 +1,199 @@
+use color_eyre::eyre::{eyre, Result};
+use serde::Serialize;
+
+use crate::cli::{Command, OutputFormat};
+use crate::client::AirflowClient;
+use crate::config::check::{check_config_dir, Severity};
+use crate::config::Config;
+use crate::models::clear::{ClearOptions, ClearRequest};
+use crate::models::conf::Conf;
+use crate::models::dag_run::DagRunTrigger;
+use crate::models::dag_runs::DagRunRecord;
+
+/// Log of a task instance as printed with `--output json`.
+#[derive(Serialize, Debug)]
+struct LogRecord {
+    dag_id: String,
+    dag_run_id: String,
+    task_id: String,
+    try_number: usize,
+    content: String,
+}
+
+/// Run `command` against the Airflow of `context` and print its result on stdout.
+pub async fn run(command: Command, output: OutputFormat, context: Option<String>) -> Result<()> {
+    // An invalid configuration cannot be loaded, it is checked file by file instead
+    if let Command::CheckConfig = command {
+        return check_config(output);
+    }
+    let mut config = Config::new()?;
+    config
+        .use_initial_context(context.as_deref())
+        .map_err(|e| eyre!(e))?;
+    let client = AirflowClient::new(&config.airflow)?;
+
+    match command {
+        Command::Runs { dag, state, limit } => {
+            let limit = limit.unwrap_or_else(|| config.get_page_size());
+            let dag_runs = client
+                .get_dag_runs(dag.as_deref(), &state, limit, 0)
+                .await?;
+            print_records(&dag_runs.get_records(None), output)
+        }
+        Command::Tasks { dag_id, run_id } => {
+            let tasks = client.get_task_instances(&dag_id, &run_id).await?;
+            print_records(&tasks.get_records(), output)
+        }
+        Command::Logs {
+            dag_id,
+            run_id,
+            task_id,
+            try_number,
+        } => {
+            let tasks = client.get_task_instances(&dag_id, &run_id).await?;
+            let task = tasks
+                .get_task(&task_id)
+                .ok_or_else(|| eyre!("No task `{}` in the run `{}`", task_id, run_id))?;
+            let try_number = try_number.unwrap_or(task.try_number as usize).max(1);
+            let content = client.get_log(task, try_number).await?;
+            match output {
+                OutputFormat::Table => print!("{}", content),
+                OutputFormat::Json => {
+                    let record = LogRecord {
+                        dag_id,
+                        dag_run_id: run_id,
+                        task_id,
+                        try_number,
+                        content,
+                    };
+                    println!("{}", serde_json::to_string_pretty(&record)?);
+                }
+            }
+            Ok(())
+        }
+        Command::Clear {
+            dag_id,
+            run_id,
+            tasks,
+            only_failed,
+            upstream,
+            downstream,
+            future,
+            past,
+            yes,
+        } => {
+            let request = ClearRequest {
+                task_ids: (!tasks.is_empty()).then_some(tasks),
+                options: ClearOptions {
+                    only_failed,
+                    include_upstream: upstream,
+                    include_downstream: downstream,
+                    include_future: future,
+                    include_past: past,
+                },
+                ..ClearRequest::dag_run(&dag_id, &run_id)
+            };
+            let task_instances = client.clear_task_instances(&request, !yes).await?;
+            if !yes {
+                eprintln!(
+                    "Dry run, {} task instance(s) would be cleared, clear them with --yes",
+                    task_instances.len()
+                );
+            }
+            print_records(&task_instances, output)
+        }
+        Command::Trigger {
+            dag_id,
+            conf,
+            logical_date,
+            note,
+        } => {
+            let conf = match conf {
+                Some(conf) => Conf(serde_json::from_str(&conf)?),
+                None => Conf::default(),
+            };
+            if !conf.0.is_object() {
+                return Err(eyre!("--conf must be a JSON object"));
+            }
+            let trigger = DagRunTrigger {
+                conf,
+                logical_date,
+                note,
+            };
+            let dag_run = client.trigger_dag_run(&dag_id, &trigger).await?;
+            print_records(&[DagRunRecord::from(&dag_run)], output)
+        }
+        Command::CheckConfig => unreachable!("checked before loading the configuration"),
+    }
+}
+
+/// Print the problems of the configuration files, the command fails if any is an error.
+fn check_config(output: OutputFormat) -> Result<()> {
+    let dir = crate::utils::get_config_dir();
+    let diagnostics = check_config_dir(&dir);
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .count();
+    match output {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&diagnostics)?),
+        OutputFormat::Table => {
+            for diagnostic in &diagnostics {
+                println!("{}", diagnostic);
+            }
+            println!(
+                "{}: {} error(s), {} warning(s)",
+                dir.display(),
+                errors,
+                diagnostics.len() - errors
+            );
+        }
+    }
+    if errors > 0 {
+        return Err(eyre!("The configuration has {} error(s)", errors));
+    }
+    Ok(())
+}
+
+/// Print `records` as a JSON array, or as a table with a column per field.
+fn print_records<T: Serialize>(records: &[T], output: OutputFormat) -> Result<()> {
+    match output {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(records)?),
+        OutputFormat::Table => print!("{}", format_table(records)?),
+    }
+    Ok(())
+}
+
+/// Align `records` in columns under a header line, the fields being flattened as in CSV.
+fn format_table<T: Serialize>(records: &[T]) -> Result<String> {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    for record in records {
+        writer.serialize(record)?;
+    }
+    let data = writer.into_inner().map_err(|e| eyre!(e.to_string()))?;
+    let rows = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(data.as_slice())
+        .into_records()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut widths: Vec<usize> = vec![];
+    for row in &rows {
+        widths.resize(widths.len().max(row.len()), 0);
+        for (width, field) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(field.chars().count());
+        }
+    }
+    let mut table = String::new();
+    for row in &rows {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(field, width)| format!("{:<width$}", field, width = width))
+            .collect::<Vec<_>>()
+            .join("  ");
+        table.push_str(line.trim_end());
+        table.push('\n');
+    }
+    Ok(table)
+}
@@ -11,13 +11,19 @@ use crate::{
 };
 
 pub mod ascii;
+pub mod clear_confirm;
 pub mod command;
 pub mod command_search;
+pub mod confirm;
 pub mod context_informations;
 pub mod fps;
+pub mod log_view;
+pub mod mark_confirm;
 pub mod shortcut;
 pub mod status_bar;
+pub mod syntax_highlight;
 pub mod table_dag_runs;
+pub mod text_search;
 
 /// `Component` is a trait that represents a visual and interactive element of the user interface.
 /// Implementors of this trait can be registered with the main application loop and will be able to receive events,
@@ -142,3 +148,6 @@ pub trait Component {
     /// * `Result<()>` - An Ok result or an error.
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()>;
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,200 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::confirm::centered_rect;
+use super::{Component, Frame};
+use crate::mode::Mode;
+use crate::models::clear::{ClearRequest, TaskInstanceReference};
+use crate::{action::Action, config::Config};
+
+/// Dry run of the clear displayed by `ClearConfirm`.
+#[derive(Default)]
+enum Preview {
+    #[default]
+    Loading,
+    Loaded(Vec<TaskInstanceReference>),
+    Failed(String),
+}
+
+/// Modal previewing the task instances affected by a clear before applying it.
+/// Every change of the options fetches a new dry run of the clear.
+#[derive(Default)]
+pub struct ClearConfirm {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    mode: Mode,
+    request: Option<ClearRequest>,
+    preview: Preview,
+}
+
+impl ClearConfirm {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Open the modal for `request`, its preview is loading.
+    pub fn open(&mut self, request: ClearRequest) {
+        self.request = Some(request);
+        self.preview = Preview::Loading;
+    }
+
+    pub fn close(&mut self) {
+        self.request = None;
+        self.preview = Preview::Loading;
+    }
+
+    /// Register the dry run of `request`, ignored if the options changed in the meantime.
+    pub fn set_preview(&mut self, request: &ClearRequest, preview: Vec<TaskInstanceReference>) {
+        if self.request.as_ref() == Some(request) {
+            self.preview = Preview::Loaded(preview);
+        }
+    }
+
+    /// Register the failure of the dry run of `request`, ignored if the options changed in the
+    /// meantime. The dry run can then be retried.
+    pub fn set_preview_failed(&mut self, request: &ClearRequest, message: String) {
+        if self.request.as_ref() == Some(request) {
+            self.preview = Preview::Failed(message);
+        }
+    }
+
+    /// Fetch the dry run of the current options again after a failure.
+    fn retry(&mut self) -> Option<Action> {
+        if !matches!(self.preview, Preview::Failed(_)) {
+            return None;
+        }
+        self.preview = Preview::Loading;
+        let request = self.request.clone()?;
+        Some(Action::PreviewClear(Box::new(request)))
+    }
+
+    fn toggle(&mut self, key: char) -> Option<Action> {
+        let request = self.request.as_mut()?;
+        let options = &mut request.options;
+        let option = match key {
+            'o' => &mut options.only_failed,
+            'u' => &mut options.include_upstream,
+            'd' => &mut options.include_downstream,
+            'f' => &mut options.include_future,
+            'p' => &mut options.include_past,
+            _ => return None,
+        };
+        *option = !*option;
+        self.preview = Preview::Loading;
+        Some(Action::PreviewClear(Box::new(request.clone())))
+    }
+}
+
+impl Component for ClearConfirm {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_mode(&mut self, mode: Mode) -> Result<()> {
+        self.mode = mode;
+        Ok(())
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if self.mode != Mode::Clear {
+            return Ok(None);
+        }
+        Ok(match key.code {
+            // The clear can only be applied once its preview is displayed
+            KeyCode::Char('y') | KeyCode::Enter if matches!(self.preview, Preview::Loaded(_)) => {
+                self.request
+                    .clone()
+                    .map(|request| Action::ApplyClear(Box::new(request)))
+            }
+            KeyCode::Char('n') | KeyCode::Esc => Some(Action::Cancel),
+            KeyCode::Char('r') => self.retry(),
+            KeyCode::Char(c) => self.toggle(c),
+            _ => None,
+        })
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        let Some(request) = &self.request else {
+            return Ok(());
+        };
+        let checkbox = |key: &'static str, label: &'static str, checked: bool| {
+            vec![
+                Span::styled(format!("<{}>", key), Style::new().blue().bold()),
+                Span::raw(format!(
+                    " [{}] {}  ",
+                    if checked { "x" } else { " " },
+                    label
+                )),
+            ]
+        };
+        let options = &request.options;
+        let mut text = vec![
+            Line::from(
+                [
+                    checkbox("o", "only failed", options.only_failed),
+                    checkbox("u", "upstream", options.include_upstream),
+                    checkbox("d", "downstream", options.include_downstream),
+                    checkbox("f", "future", options.include_future),
+                    checkbox("p", "past", options.include_past),
+                ]
+                .concat(),
+            ),
+            Line::from(""),
+        ];
+        match &self.preview {
+            Preview::Loading => text.push(Line::styled("Loading...", Style::new().dark_gray())),
+            Preview::Failed(message) => {
+                text.push(Line::styled(message.clone(), Style::new().red()))
+            }
+            Preview::Loaded(preview) => {
+                text.push(Line::styled(
+                    format!("{} task instance(s) will be cleared:", preview.len()),
+                    Style::new().yellow(),
+                ));
+                for task_instance in preview {
+                    text.push(Line::from(format!(
+                        "  {} {} {}",
+                        task_instance.dag_id, task_instance.dag_run_id, task_instance.task_id
+                    )));
+                }
+            }
+        }
+        text.push(Line::from(""));
+        let mut keys = vec![
+            Span::styled("<y>", Style::new().blue().bold()),
+            Span::raw(" clear   "),
+            Span::styled("<n>", Style::new().blue().bold()),
+            Span::raw(" cancel"),
+        ];
+        if let Preview::Failed(_) = self.preview {
+            keys.push(Span::raw("   "));
+            keys.push(Span::styled("<r>", Style::new().blue().bold()));
+            keys.push(Span::raw(" retry"));
+        }
+        text.push(Line::from(keys));
+
+        let title = match &request.task_ids {
+            Some(task_ids) => format!(" Clear {} of {} ", task_ids.join(", "), request.dag_run_id),
+            None => format!(" Clear {} ", request.dag_run_id),
+        };
+        let popup_area = centered_rect(area, 80, text.len() as u16 + 2);
+        let popup = Paragraph::new(text).block(
+            Block::default()
+                .title(title)
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::LightYellow)),
+        );
+        f.render_widget(Clear, popup_area);
+        f.render_widget(popup, popup_area);
+        Ok(())
+    }
+}
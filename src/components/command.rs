@@ -37,6 +37,60 @@ impl Command {
     }
 }
 
+/// Parse a command typed in the command bar (without the leading `:`) into the
+/// action it dispatches to.
+///
+/// Supported commands:
+/// * `q`, `q!`, `quit` - quit arvz
+/// * `dags` - list the dags
+/// * `runs [dag_id]` - list the dag runs of every dag, or of `dag_id` only
+/// * `tasks` - list the task instances of the selected dag run
+/// * `code` - show the source code of the selected dag run
+/// * `pools` - list the pools
+/// * `ctx <name>` - switch to another airflow context
+/// * `trigger [dag_id]` - trigger a new run of a dag, the dag of the selected dag run by default
+/// * `export [dir]` - export the logs of every task instance of the selected dag run
+/// * `export-table <file.csv|file.json>` - export the dag runs or task instances table
+/// * `<line>` - jump to a line of the source code
+pub fn parse_command(input: &str) -> Result<Action, String> {
+    let mut words = input.split_whitespace();
+    let Some(name) = words.next() else {
+        return Err(String::from("Empty command"));
+    };
+    let args: Vec<&str> = words.collect();
+    if let (Ok(line), []) = (name.parse::<usize>(), args.as_slice()) {
+        return Ok(Action::GotoLine(line));
+    }
+
+    let action = match (name, args.as_slice()) {
+        ("q" | "q!" | "quit", []) => Action::Quit,
+        ("runs" | "dagruns", []) => Action::Runs(None),
+        ("runs" | "dagruns", [dag_id]) => Action::Runs(Some(dag_id.to_string())),
+        ("tasks", []) => Action::Task,
+        ("code", []) => Action::Code,
+        ("dags", []) => Action::Dag,
+        ("pools", []) => Action::Pool,
+        ("ctx" | "context", [name]) => Action::SwitchContext(name.to_string()),
+        ("trigger", []) => Action::Trigger,
+        ("trigger", [dag_id]) => Action::TriggerDag(dag_id.to_string()),
+        ("export-table", [path]) => Action::ExportTable(path.to_string()),
+        ("export", []) => Action::ExportLogs,
+        ("export", [dir]) => Action::ExportLogsTo(dir.to_string()),
+        ("q" | "q!" | "quit" | "dags" | "tasks" | "code" | "pools", _) => {
+            return Err(format!("`{}` takes no argument", name))
+        }
+        ("runs" | "dagruns", _) => return Err(String::from("Usage: runs [dag_id]")),
+        ("ctx" | "context", _) => return Err(String::from("Usage: ctx <name>")),
+        ("trigger", _) => return Err(String::from("Usage: trigger [dag_id]")),
+        ("export", _) => return Err(String::from("Usage: export [dir]")),
+        ("export-table", _) => {
+            return Err(String::from("Usage: export-table <file.csv|file.json>"))
+        }
+        _ => return Err(format!("Unknown command `{}`", name)),
+    };
+    Ok(action)
+}
+
 impl Component for Command {
     fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
         self.command_tx = Some(tx);
@@ -54,9 +108,17 @@ impl Component for Command {
     }
 
     fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
-        if self.mode == Mode::Command {
-            get_user_input_by_key(key.code, &mut self.command);
+        if self.mode != Mode::Command {
+            return Ok(None);
         }
+        if key.code == KeyCode::Enter {
+            let input = self.command.take().unwrap_or_default();
+            return Ok(Some(match parse_command(&input) {
+                Ok(action) => action,
+                Err(e) => Action::CommandFailed(e),
+            }));
+        }
+        get_user_input_by_key(key.code, &mut self.command);
         Ok(None)
     }
 
@@ -80,3 +142,6 @@ impl Component for Command {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,95 @@
+use crossterm::event::KeyModifiers;
+
+use super::*;
+
+fn type_keys(command: &mut Command, input: &str) -> Vec<Option<Action>> {
+    input
+        .chars()
+        .map(KeyCode::Char)
+        .chain([KeyCode::Enter])
+        .map(|code| {
+            command
+                .handle_key_events(KeyEvent::new(code, KeyModifiers::NONE))
+                .unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn parse_commands() {
+    assert_eq!(parse_command("q"), Ok(Action::Quit));
+    assert_eq!(parse_command("  quit  "), Ok(Action::Quit));
+    assert_eq!(parse_command("runs"), Ok(Action::Runs(None)));
+    assert_eq!(
+        parse_command("dagruns etl_daily"),
+        Ok(Action::Runs(Some(String::from("etl_daily"))))
+    );
+    assert_eq!(parse_command("dags"), Ok(Action::Dag));
+    assert_eq!(
+        parse_command("ctx staging"),
+        Ok(Action::SwitchContext(String::from("staging")))
+    );
+    assert_eq!(parse_command("trigger"), Ok(Action::Trigger));
+    assert_eq!(
+        parse_command("trigger etl"),
+        Ok(Action::TriggerDag(String::from("etl")))
+    );
+    assert_eq!(parse_command("export"), Ok(Action::ExportLogs));
+    assert_eq!(
+        parse_command("export-table runs.csv"),
+        Ok(Action::ExportTable(String::from("runs.csv")))
+    );
+    assert_eq!(parse_command("42"), Ok(Action::GotoLine(42)));
+}
+
+#[test]
+fn parse_invalid_commands() {
+    assert_eq!(parse_command(""), Err(String::from("Empty command")));
+    assert_eq!(
+        parse_command("frobnicate"),
+        Err(String::from("Unknown command `frobnicate`"))
+    );
+    assert_eq!(
+        parse_command("dags etl"),
+        Err(String::from("`dags` takes no argument"))
+    );
+    assert_eq!(parse_command("ctx"), Err(String::from("Usage: ctx <name>")));
+    assert_eq!(
+        parse_command("runs a b"),
+        Err(String::from("Usage: runs [dag_id]"))
+    );
+    assert_eq!(
+        parse_command("42 43"),
+        Err(String::from("Unknown command `42`"))
+    );
+}
+
+#[test]
+fn enter_dispatches_the_typed_command() {
+    let mut command = Command::new();
+    let actions = type_keys(&mut command, "runs etl");
+    assert_eq!(
+        actions.last(),
+        Some(&Some(Action::Runs(Some(String::from("etl")))))
+    );
+    assert!(actions[..actions.len() - 1].iter().all(Option::is_none));
+    assert_eq!(command.command, None);
+}
+
+#[test]
+fn keys_are_ignored_outside_of_command_mode() {
+    let mut command = Command::new();
+    command.handle_mode(Mode::DagRun).unwrap();
+    assert!(type_keys(&mut command, "q").iter().all(Option::is_none));
+}
+
+#[test]
+fn enter_reports_an_invalid_command() {
+    let mut command = Command::new();
+    assert_eq!(
+        type_keys(&mut command, "frobnicate").last(),
+        Some(&Some(Action::CommandFailed(String::from(
+            "Unknown command `frobnicate`"
+        ))))
+    );
+}
@@ -24,6 +24,7 @@ pub struct CommandSearch {
     config: Config,
     mode: Mode,
     user_search: Option<String>,
+    text_search: Option<String>,
 }
 
 impl CommandSearch {
@@ -33,6 +34,7 @@ impl CommandSearch {
             config: Config::default(),
             mode: Mode::DagRun,
             user_search: None,
+            text_search: None,
         }
     }
 }
@@ -49,6 +51,10 @@ impl Component for CommandSearch {
     }
 
     fn handle_mode(&mut self, mode: Mode) -> Result<()> {
+        // Each search inside a log or a source code starts from an empty pattern
+        if mode == Mode::TextSearch && self.mode != Mode::TextSearch {
+            self.text_search = None;
+        }
         self.mode = mode;
         Ok(())
     }
@@ -57,6 +63,18 @@ impl Component for CommandSearch {
         if self.mode == Mode::Search {
             get_user_input_by_key(key.code, &mut self.user_search);
         }
+        if self.mode == Mode::TextSearch {
+            return Ok(match key.code {
+                KeyCode::Enter => Some(Action::SubmitSearch),
+                KeyCode::Esc => Some(Action::ClearSearch),
+                code => {
+                    get_user_input_by_key(code, &mut self.text_search);
+                    Some(Action::SearchText(
+                        self.text_search.clone().unwrap_or_default(),
+                    ))
+                }
+            });
+        }
         Ok(None)
     }
 
@@ -66,11 +84,18 @@ impl Component for CommandSearch {
 
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
         // draw the search bar
-        let line = Line::from(vec![if let Some(search) = &self.user_search {
-            Span::raw(search)
+        let line = if self.mode == Mode::TextSearch {
+            Line::from(vec![
+                Span::raw("/"),
+                Span::raw(self.text_search.as_deref().unwrap_or("")),
+            ])
         } else {
-            Span::raw("")
-        }]);
+            Line::from(vec![if let Some(search) = &self.user_search {
+                Span::raw(search)
+            } else {
+                Span::raw("")
+            }])
+        };
         let search_bar = Paragraph::new(line).block(
             Block::default()
                 .borders(Borders::ALL)
@@ -0,0 +1,102 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{Component, Frame};
+use crate::mode::Mode;
+use crate::{action::Action, config::Config};
+
+/// Modal asking the user to confirm an action before it is dispatched.
+#[derive(Default)]
+pub struct Confirm {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    mode: Mode,
+    message: String,
+    action: Option<Action>,
+}
+
+impl Confirm {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask for a confirmation of `action`, described by `message`.
+    pub fn ask(&mut self, message: String, action: Action) {
+        self.message = message;
+        self.action = Some(action);
+    }
+
+    /// Take the action waiting for a confirmation.
+    pub fn take_action(&mut self) -> Option<Action> {
+        self.action.take()
+    }
+}
+
+impl Component for Confirm {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_mode(&mut self, mode: Mode) -> Result<()> {
+        self.mode = mode;
+        Ok(())
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if self.mode != Mode::Confirm {
+            return Ok(None);
+        }
+        Ok(match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => Some(Action::Accept),
+            KeyCode::Char('n') | KeyCode::Esc => Some(Action::Cancel),
+            _ => None,
+        })
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        let text = vec![
+            Line::from(self.message.as_str()),
+            Line::from(""),
+            Line::from(vec![
+                Span::styled("<y>", Style::new().blue().bold()),
+                Span::raw(" confirm   "),
+                Span::styled("<n>", Style::new().blue().bold()),
+                Span::raw(" cancel"),
+            ]),
+        ];
+        let popup_area = centered_rect(area, 60, text.len() as u16 + 2);
+        let popup = Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .title(" Confirm ")
+                    .title_alignment(Alignment::Center)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::LightYellow)),
+            );
+        f.render_widget(Clear, popup_area);
+        f.render_widget(popup, popup_area);
+        Ok(())
+    }
+}
+
+/// A rectangle of `percent_x` of the width of `area` and `height` lines, centered in `area`.
+pub fn centered_rect(area: Rect, percent_x: u16, height: u16) -> Rect {
+    let height = height.min(area.height);
+    let width = area.width * percent_x / 100;
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
@@ -18,6 +18,8 @@ use crate::{
 pub struct ContextInformation {
     command_tx: Option<UnboundedSender<Action>>,
     config: Config,
+    context: String,
+    airflow_version: Option<String>,
     total_dag_runs: u32,
     total_dag_runs_running: u32,
     total_dag_runs_failed: u32,
@@ -31,6 +33,8 @@ impl ContextInformation {
         Self {
             command_tx: None,
             config: Config::default(),
+            context: String::new(),
+            airflow_version: None,
             total_dag_runs: 0,
             total_dag_runs_running: 0,
             total_dag_runs_failed: 0,
@@ -40,6 +44,11 @@ impl ContextInformation {
         }
     }
 
+    /// Show `version` next to the context, until another context is used.
+    pub fn set_airflow_version(&mut self, version: &str) {
+        self.airflow_version = Some(version.to_string());
+    }
+
     pub fn register_context_information(&mut self, dag_runs: &DagRuns) {
         self.total_dag_runs = dag_runs.get_total_entries();
         self.total_dag_runs_running = dag_runs.get_count_dag_run_running();
@@ -57,6 +66,8 @@ impl Component for ContextInformation {
     }
 
     fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.context = config.current_context.clone();
+        self.airflow_version = None;
         self.config = config;
         Ok(())
     }
@@ -69,6 +80,16 @@ impl Component for ContextInformation {
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
         // Align the value on the right to the same starting point
         let text = vec![
+            Line::from(vec![
+                Span::styled("Context            : ", Style::new().yellow()),
+                Span::styled(self.context.as_str(), Style::new().magenta()),
+                Span::raw(
+                    self.airflow_version
+                        .as_ref()
+                        .map(|version| format!(" (Airflow {})", version))
+                        .unwrap_or_default(),
+                ),
+            ]),
             Line::from(vec![
                 Span::styled("Dag Runs Number    : ", Style::new().yellow()),
                 Span::raw(format!("{}", self.total_dag_runs)),
@@ -0,0 +1,127 @@
+use ratatui::prelude::*;
+
+use crate::models::log_line::{LogLevel, LogLine};
+
+/// Kind of a line displayed in the log view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DisplayedLine {
+    // A line of the log, with the level of the last parsed line for the lines following it
+    Log(Option<LogLevel>),
+    // The `***` headers folded into a single line
+    Headers,
+}
+
+/// The log as displayed: the `***` headers folded and the lines below `min_level` filtered out.
+/// It is built again whenever the log or the display options change.
+#[derive(Debug)]
+pub struct LogView {
+    pub(crate) text: String,
+    lines: Vec<DisplayedLine>,
+    pub(crate) min_level: Option<LogLevel>,
+    pub(crate) fold_headers: bool,
+    // Length of the log and display options the text was built from
+    built: Option<(usize, Option<LogLevel>, bool)>,
+}
+
+impl Default for LogView {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            lines: vec![],
+            min_level: None,
+            fold_headers: true,
+            built: None,
+        }
+    }
+}
+
+impl LogView {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Display only the lines of the next level, back to every line after the most severe one.
+    pub fn next_min_level(&mut self) {
+        self.min_level = match self.min_level {
+            None => Some(LogLevel::Info),
+            Some(LogLevel::Info) => Some(LogLevel::Warning),
+            Some(LogLevel::Warning) => Some(LogLevel::Error),
+            Some(_) => None,
+        };
+    }
+
+    /// Build the displayed text again on the next refresh, another log was loaded.
+    pub fn invalidate(&mut self) {
+        self.built = None;
+    }
+
+    /// Build the displayed text from `log`, unless it was already built.
+    /// Returns `true` if the displayed text changed.
+    pub fn refresh(&mut self, log: &str) -> bool {
+        let built = (log.len(), self.min_level, self.fold_headers);
+        if self.built == Some(built) {
+            return false;
+        }
+        self.built = Some(built);
+        self.text.clear();
+        self.lines.clear();
+
+        let mut level = None;
+        let mut headers: Vec<&str> = vec![];
+        for line in log.lines() {
+            if LogLine::is_header(line) {
+                headers.push(line);
+                continue;
+            }
+            self.push_headers(&mut headers);
+            if let Some(log_line) = LogLine::parse(line) {
+                level = Some(log_line.level);
+            }
+            if self.min_level.is_none() || level >= self.min_level {
+                self.push_line(line, DisplayedLine::Log(level));
+            }
+        }
+        self.push_headers(&mut headers);
+        true
+    }
+
+    fn push_headers(&mut self, headers: &mut Vec<&str>) {
+        match headers.as_slice() {
+            [] => {}
+            [first, others @ ..] if self.fold_headers && !others.is_empty() => {
+                let folded = format!("{} (+{} folded)", first, others.len());
+                self.push_line(&folded, DisplayedLine::Headers);
+            }
+            _ => {
+                for header in headers.iter() {
+                    self.push_line(header, DisplayedLine::Headers);
+                }
+            }
+        }
+        headers.clear();
+    }
+
+    fn push_line(&mut self, line: &str, displayed_line: DisplayedLine) {
+        self.text.push_str(line);
+        self.text.push('\n');
+        self.lines.push(displayed_line);
+    }
+
+    /// Style of the displayed line number `line`, coloured after its level.
+    pub fn get_line_style(&self, line: usize) -> Style {
+        match self.lines.get(line) {
+            Some(DisplayedLine::Headers) => Style::new().dark_gray().italic(),
+            Some(DisplayedLine::Log(Some(LogLevel::Critical | LogLevel::Error))) => {
+                Style::new().light_red()
+            }
+            Some(DisplayedLine::Log(Some(LogLevel::Warning))) => Style::new().yellow(),
+            Some(DisplayedLine::Log(Some(LogLevel::Debug))) => Style::new().dark_gray(),
+            _ => Style::default(),
+        }
+    }
+
+    /// Description of the display options for the title of the view.
+    pub fn get_title(&self) -> Option<String> {
+        self.min_level.map(|level| format!("{}+", level))
+    }
+}
@@ -0,0 +1,138 @@
+use color_eyre::eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{prelude::*, widgets::*};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::confirm::centered_rect;
+use super::{Component, Frame};
+use crate::mode::Mode;
+use crate::models::mark::{MarkRequest, MarkState};
+use crate::{action::Action, config::Config};
+
+/// Modal confirming the mark of a dag run or a task instance as success or failed.
+/// The upstream and downstream tasks of a task instance can be marked along with it.
+#[derive(Default)]
+pub struct MarkConfirm {
+    command_tx: Option<UnboundedSender<Action>>,
+    config: Config,
+    mode: Mode,
+    request: Option<MarkRequest>,
+}
+
+impl MarkConfirm {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn open(&mut self, request: MarkRequest) {
+        self.request = Some(request);
+    }
+
+    pub fn close(&mut self) {
+        self.request = None;
+    }
+}
+
+impl Component for MarkConfirm {
+    fn register_action_handler(&mut self, tx: UnboundedSender<Action>) -> Result<()> {
+        self.command_tx = Some(tx);
+        Ok(())
+    }
+
+    fn register_config_handler(&mut self, config: Config) -> Result<()> {
+        self.config = config;
+        Ok(())
+    }
+
+    fn handle_mode(&mut self, mode: Mode) -> Result<()> {
+        self.mode = mode;
+        Ok(())
+    }
+
+    fn handle_key_events(&mut self, key: KeyEvent) -> Result<Option<Action>> {
+        if self.mode != Mode::Mark {
+            return Ok(None);
+        }
+        let Some(request) = self.request.as_mut() else {
+            return Ok(None);
+        };
+        let is_task = request.task_id.is_some();
+        Ok(match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => {
+                Some(Action::ApplyMark(Box::new(request.clone())))
+            }
+            KeyCode::Char('n') | KeyCode::Esc => Some(Action::Cancel),
+            KeyCode::Char('u') if is_task => {
+                request.include_upstream = !request.include_upstream;
+                None
+            }
+            KeyCode::Char('d') if is_task => {
+                request.include_downstream = !request.include_downstream;
+                None
+            }
+            _ => None,
+        })
+    }
+
+    fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
+        let Some(request) = &self.request else {
+            return Ok(());
+        };
+        let state_style = match request.state {
+            MarkState::Success => Style::new().green().bold(),
+            MarkState::Failed => Style::new().red().bold(),
+        };
+        let mut text = match &request.task_id {
+            Some(task_id) => vec![
+                Line::from(vec![
+                    Span::raw(format!("Mark {} of {} as ", task_id, request.dag_run_id)),
+                    Span::styled(request.state.to_string(), state_style),
+                    Span::raw(" ?"),
+                ]),
+                Line::from(""),
+                Line::from(vec![
+                    Span::styled("<u>", Style::new().blue().bold()),
+                    Span::raw(format!(
+                        " [{}] upstream  ",
+                        if request.include_upstream { "x" } else { " " }
+                    )),
+                    Span::styled("<d>", Style::new().blue().bold()),
+                    Span::raw(format!(
+                        " [{}] downstream",
+                        if request.include_downstream { "x" } else { " " }
+                    )),
+                ]),
+            ],
+            None => vec![Line::from(vec![
+                Span::raw(format!(
+                    "Mark {} of {} as ",
+                    request.dag_run_id, request.dag_id
+                )),
+                Span::styled(request.state.to_string(), state_style),
+                Span::raw(" ?"),
+            ])],
+        };
+        text.push(Line::from(""));
+        text.push(Line::from(vec![
+            Span::styled("<y>", Style::new().blue().bold()),
+            Span::raw(" confirm   "),
+            Span::styled("<n>", Style::new().blue().bold()),
+            Span::raw(" cancel"),
+        ]));
+
+        let popup_area = centered_rect(area, 60, text.len() as u16 + 2);
+        let popup = Paragraph::new(text)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::default()
+                    .title(" Mark ")
+                    .title_alignment(Alignment::Center)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::LightYellow)),
+            );
+        f.render_widget(Clear, popup_area);
+        f.render_widget(popup, popup_area);
+        Ok(())
+    }
+}
@@ -53,15 +53,37 @@ impl Component for Shortcut {
 
     fn draw(&mut self, f: &mut Frame<'_>, area: Rect) -> Result<()> {
         // Loop through keybindings by mode and display them
+        let Some(keybindings) = self.config.keybindings.get(&self.mode) else {
+            return Ok(());
+        };
+        // Sorted by key, the order of the map changes from a run to another
+        let mut keybindings = keybindings
+            .iter()
+            .filter_map(|(shortcut, action)| Some((key_event_to_string(shortcut.first()?), action)))
+            .collect::<Vec<_>>();
+        keybindings.sort_by(|(a, _), (b, _)| a.cmp(b));
         // Get the number of keybindings for the current mode
-        let num_keybindings = self.config.keybindings.get(&self.mode).unwrap().len() as f64;
+        let num_keybindings = keybindings.len() as f64;
 
         let number_of_columns = (num_keybindings / 6f64).ceil() as u16;
 
+        let max_shortcut_len = keybindings
+            .iter()
+            .map(|(shortcut, _)| shortcut.len())
+            .max()
+            .unwrap_or_default();
+        let max_action_len = keybindings
+            .iter()
+            .map(|(_, action)| action.to_string().len())
+            .max()
+            .unwrap_or_default();
+        // Wide enough for the longest shortcut and action, with a space between the columns
+        let column_width = (max_shortcut_len + max_action_len + 4) as u16;
+
         let mut constraints = vec![];
 
         for _ in 0..number_of_columns {
-            constraints.push(Constraint::Length(21));
+            constraints.push(Constraint::Length(column_width));
         }
 
         let layout = Layout::default()
@@ -70,23 +92,10 @@ impl Component for Shortcut {
             .split(area);
 
         let mut text: Vec<Line> = vec![];
-        let mut cnt_keybindings = 1;
-        let max_shortcut_len = self
-            .config
-            .keybindings
-            .get(&self.mode)
-            .unwrap()
-            .iter()
-            .map(|(shortcut, _)| key_event_to_string(&shortcut[0]).len())
-            .max()
-            .unwrap();
-        for (shortcut, action) in self.config.keybindings.get(&self.mode).unwrap() {
+        for (cnt_keybindings, (shortcut, action)) in (1..).zip(&keybindings) {
             text.push(Line::from(vec![
-                Span::styled(
-                    format!("<{}>", key_event_to_string(&shortcut[0])),
-                    Style::new().blue().bold(),
-                ),
-                Span::raw(" ".repeat(max_shortcut_len - key_event_to_string(&shortcut[0]).len())),
+                Span::styled(format!("<{}>", shortcut), Style::new().blue().bold()),
+                Span::raw(" ".repeat(max_shortcut_len - shortcut.len())),
                 Span::styled(format!(" {}", action), Style::new()),
             ]));
             if cnt_keybindings % 6 == 0 || cnt_keybindings as f64 == num_keybindings {
@@ -96,7 +105,6 @@ impl Component for Shortcut {
                 );
                 text = vec![];
             }
-            cnt_keybindings += 1;
         }
         Ok(())
     }
@@ -24,8 +24,13 @@ pub struct StatusBar {
     config: Config,
     mode: Mode,
     pub(crate) mode_breadcrumb: Vec<Mode>,
+    message: Option<(String, Color)>,
+    message_ticks: u8,
 }
 
+// Number of ticks a message stays displayed in the status bar
+const MESSAGE_TICKS: u8 = 5;
+
 impl StatusBar {
     pub fn new() -> Self {
         Self {
@@ -33,6 +38,8 @@ impl StatusBar {
             config: Config::default(),
             mode: Mode::DagRun,
             mode_breadcrumb: vec![],
+            message: None,
+            message_ticks: 0,
         }
     }
 
@@ -53,7 +60,23 @@ impl Component for StatusBar {
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
-        {}
+        match action {
+            Action::Error(message) => {
+                self.message = Some((message, Color::Red));
+                self.message_ticks = MESSAGE_TICKS;
+            }
+            Action::Info(message) => {
+                self.message = Some((message, Color::Green));
+                self.message_ticks = MESSAGE_TICKS;
+            }
+            Action::Tick if self.message.is_some() => {
+                self.message_ticks = self.message_ticks.saturating_sub(1);
+                if self.message_ticks == 0 {
+                    self.message = None;
+                }
+            }
+            _ => {}
+        }
         Ok(None)
     }
 
@@ -86,6 +109,13 @@ impl Component for StatusBar {
             .style(Style::default().fg(Color::DarkGray).bg(Color::LightCyan));
         f.render_widget(para, block[self.mode_breadcrumb.len() - 1]);
         self.mode_breadcrumb.pop();
+
+        if let Some((message, color)) = &self.message {
+            let para = Paragraph::new(message.as_str())
+                .alignment(Alignment::Right)
+                .style(Style::default().fg(*color));
+            f.render_widget(para, area);
+        }
         Ok(())
     }
 }
@@ -0,0 +1,100 @@
+use std::ops::Range;
+
+use ratatui::prelude::*;
+use syntect::easy::HighlightLines;
+use syntect::highlighting::{FontStyle, Theme, ThemeSet};
+use syntect::parsing::SyntaxSet;
+
+// Theme bundled with syntect used to colour the source code
+const THEME: &str = "base16-ocean.dark";
+
+/// Styles of the byte ranges of a line.
+pub type LineStyles = Vec<(Range<usize>, Style)>;
+
+/// Python syntax highlighting of the source code of the dags, with the grammar bundled in the
+/// binary. The source code is highlighted once when loaded, not on every frame: it must be
+/// invalidated whenever another source code is loaded.
+#[derive(Debug, Default)]
+pub struct SyntaxHighlight {
+    syntax_set: Option<SyntaxSet>,
+    theme: Option<Theme>,
+    lines: Vec<LineStyles>,
+    highlighted: bool,
+}
+
+impl SyntaxHighlight {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Highlight the source code again on the next refresh, another one was loaded.
+    pub fn invalidate(&mut self) {
+        self.highlighted = false;
+    }
+
+    /// Highlight `code`, unless it was already highlighted.
+    pub fn refresh(&mut self, code: &str) {
+        if self.highlighted {
+            return;
+        }
+        self.highlighted = true;
+        // Loading the grammars takes a while, it is only done once the code view is opened
+        let syntax_set = self
+            .syntax_set
+            .get_or_insert_with(SyntaxSet::load_defaults_nonewlines);
+        let theme = self.theme.get_or_insert_with(|| {
+            ThemeSet::load_defaults()
+                .themes
+                .remove(THEME)
+                .unwrap_or_default()
+        });
+        let syntax = syntax_set
+            .find_syntax_by_extension("py")
+            .unwrap_or_else(|| syntax_set.find_syntax_plain_text());
+        let mut highlighter = HighlightLines::new(syntax, theme);
+        self.lines = code
+            .lines()
+            .map(|line| match highlighter.highlight_line(line, syntax_set) {
+                Ok(regions) => {
+                    let mut start = 0;
+                    regions
+                        .into_iter()
+                        .map(|(style, text)| {
+                            let range = start..start + text.len();
+                            start = range.end;
+                            (range, to_style(style))
+                        })
+                        .collect()
+                }
+                Err(_) => vec![(0..line.len(), Style::default())],
+            })
+            .collect();
+    }
+
+    /// Styles of the line number `line`, the whole line is unstyled if it was not highlighted.
+    pub fn get_line_styles(&self, line: usize, content: &str) -> LineStyles {
+        self.lines
+            .get(line)
+            .cloned()
+            .unwrap_or_else(|| vec![(0..content.len(), Style::default())])
+    }
+}
+
+/// Convert a syntect style, the background of the theme is ignored to keep the terminal one.
+fn to_style(style: syntect::highlighting::Style) -> Style {
+    let mut result = Style::new().fg(Color::Rgb(
+        style.foreground.r,
+        style.foreground.g,
+        style.foreground.b,
+    ));
+    if style.font_style.contains(FontStyle::BOLD) {
+        result = result.bold();
+    }
+    if style.font_style.contains(FontStyle::ITALIC) {
+        result = result.italic();
+    }
+    if style.font_style.contains(FontStyle::UNDERLINE) {
+        result = result.underlined();
+    }
+    result
+}
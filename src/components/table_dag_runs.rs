@@ -1,21 +1,28 @@
-use std::usize;
+use std::path::Path;
 use std::{collections::HashMap, time::Duration};
 
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{eyre, Result};
 use color_eyre::owo_colors::OwoColorize;
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::widgets::block::title;
 use ratatui::{prelude::*, widgets::*};
-use reqwest::Client;
 use serde::de::Unexpected::Str;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc::UnboundedSender;
 use tracing_subscriber::fmt::format;
 
+use super::log_view::LogView;
+use super::syntax_highlight::SyntaxHighlight;
+use super::text_search::TextSearch;
 use super::{Component, Frame};
+use crate::export::export_records;
 use crate::mode::Mode;
+use crate::models::dag::Dag;
 use crate::models::dag_run::DagRun;
 use crate::models::dag_runs::DagRuns;
+use crate::models::dags::Dags;
+use crate::models::pools::Pools;
+use crate::models::task::Task;
 use crate::models::tasks::Tasks;
 use crate::utils::get_user_input_by_key;
 use crate::{
@@ -30,13 +37,24 @@ pub struct TableDagRuns {
     mode: Mode,
     columns: Vec<&'static str>,
     pub(crate) dag_runs: DagRuns,
+    // Dag whose runs are listed, every dag if `None`
+    pub(crate) dag_id: Option<String>,
     pub(crate) table_state: TableState,
     pub(crate) user_search: Option<String>,
-    client: Client,
     pub(crate) tasks: Option<Tasks>,
     pub(crate) table_tasks_state: TableState,
-    pub(crate) log: String,
-    pub(crate) code: String,
+    pub(crate) pools: Pools,
+    pub(crate) dags: Dags,
+    pub(crate) table_dags_state: TableState,
+    pub(crate) table_pools_state: TableState,
+    log: String,
+    pub(crate) following_log: bool,
+    code: String,
+    pub(crate) text_search: TextSearch,
+    pub(crate) log_view: LogView,
+    syntax_highlight: SyntaxHighlight,
+    // Line of the source code the user jumped to, emphasized in the gutter
+    pub(crate) goto_line: Option<usize>,
     pub try_number: usize,
     pub scrollbar: Option<ScrollbarState>,
     pub position: Option<usize>,
@@ -58,13 +76,22 @@ impl TableDagRuns {
                 "EXTERNAL TRIGGER",
             ],
             dag_runs: DagRuns::default(),
+            dag_id: None,
             table_state: TableState::default(),
             user_search: None,
-            client: Client::new(),
             tasks: None,
             table_tasks_state: TableState::default(),
+            pools: Pools::default(),
+            dags: Dags::default(),
+            table_dags_state: TableState::default(),
+            table_pools_state: TableState::default(),
             log: String::from(""),
+            following_log: false,
             code: String::from(""),
+            text_search: TextSearch::new(),
+            log_view: LogView::new(),
+            syntax_highlight: SyntaxHighlight::new(),
+            goto_line: None,
             try_number: 1,
             scrollbar: None,
             position: None,
@@ -75,6 +102,135 @@ impl TableDagRuns {
     pub fn set_dag_runs(&mut self, dag_runs: DagRuns) {
         self.dag_runs = dag_runs;
     }
+
+    /// The dag selected in the dag table.
+    pub fn get_selected_dag(&self) -> Option<&Dag> {
+        self.dags.dags.get(self.table_dags_state.selected()?)
+    }
+
+    /// The dag run selected in the table, taking the search filter into account.
+    pub fn get_selected_dag_run(&self) -> Option<&DagRun> {
+        let selected = self.table_state.selected()?;
+        if let Some(search) = &self.user_search {
+            self.dag_runs
+                .filter_runs_by_dag_id(search)
+                .get(selected)
+                .copied()
+        } else {
+            self.dag_runs.dag_runs.get(selected)
+        }
+    }
+
+    /// Replace the log by the one of another task instance or try.
+    pub fn set_log(&mut self, log: String) {
+        self.log = log;
+        self.log_view.invalidate();
+        self.text_search.invalidate();
+    }
+
+    /// Replace the source code by the one of another dag.
+    pub fn set_code(&mut self, code: String) {
+        self.code = code;
+        self.syntax_highlight.invalidate();
+        self.text_search.invalidate();
+    }
+
+    /// Append the new content of the followed log, scrolling to its end unless the user
+    /// scrolled up.
+    pub fn append_log(&mut self, content: &str) {
+        let height = self.heigh_lines as usize;
+        let at_bottom = match self.position {
+            Some(position) => position + height >= self.displayed_text().lines().count(),
+            None => true,
+        };
+        self.log.push_str(content);
+        if at_bottom {
+            let lines = self.displayed_text().lines().count();
+            self.position = Some(lines.saturating_sub(height));
+        }
+    }
+
+    /// The log as displayed, or the source code.
+    fn displayed_text(&mut self) -> &str {
+        if self.mode != Mode::Log {
+            return &self.code;
+        }
+        if self.log_view.refresh(&self.log) {
+            self.text_search.invalidate();
+        }
+        &self.log_view.text
+    }
+
+    /// Fold or unfold the `***` headers of the log.
+    pub fn toggle_log_headers(&mut self) {
+        self.log_view.fold_headers = !self.log_view.fold_headers;
+        self.position = Some(0);
+    }
+
+    /// Filter the log on the next level.
+    pub fn next_log_level(&mut self) {
+        self.log_view.next_min_level();
+        self.position = Some(0);
+    }
+
+    /// Search `pattern` in the displayed log or source code, and scroll to its first match
+    /// from the top of the view.
+    pub fn search_text(&mut self, pattern: &str) {
+        self.text_search.set_pattern(pattern);
+        self.displayed_text();
+        let text = if self.mode == Mode::Log {
+            &self.log_view.text
+        } else {
+            &self.code
+        };
+        self.text_search.refresh(self.mode, text);
+        if let Some(text_match) = self.text_search.select_from(self.position.unwrap_or(0)) {
+            self.scroll_to(text_match.line);
+        }
+    }
+
+    /// Scroll the source code to the line `number`, counted from 1.
+    pub fn go_to_line(&mut self, number: usize) {
+        let line = number.clamp(1, self.code.lines().count().max(1)) - 1;
+        self.goto_line = Some(line);
+        self.scroll_to(line);
+    }
+
+    /// Scroll the log or source code so that `line` is in the middle of the view.
+    fn scroll_to(&mut self, line: usize) {
+        self.position = Some(line.saturating_sub(self.heigh_lines as usize / 2));
+    }
+
+    /// Write the dag runs table, filtered as displayed, or the task instances table of `mode`
+    /// to `path`. Returns the number of exported rows.
+    pub fn export_table(&self, mode: Mode, path: &Path) -> Result<usize> {
+        match mode {
+            Mode::DagRun => {
+                let records = self.dag_runs.get_records(self.user_search.as_deref());
+                export_records(&records, path)?;
+                Ok(records.len())
+            }
+            Mode::Task => {
+                let Some(tasks) = &self.tasks else {
+                    return Err(eyre!("Tasks are still loading"));
+                };
+                let records = tasks.get_records();
+                export_records(&records, path)?;
+                Ok(records.len())
+            }
+            _ => Err(eyre!(
+                "Only the dag runs and task instances tables can be exported"
+            )),
+        }
+    }
+
+    /// The task instance selected in the task table.
+    pub fn get_selected_task(&self) -> Option<&Task> {
+        self.tasks
+            .as_ref()?
+            .task_instances
+            .get(self.table_tasks_state.selected()?)
+    }
 }
 
 impl Component for TableDagRuns {
@@ -89,6 +245,13 @@ impl Component for TableDagRuns {
     }
 
     fn handle_mode(&mut self, mode: Mode) -> Result<()> {
+        // The view stays displayed as is behind the modals and the command bars
+        if matches!(
+            mode,
+            Mode::Confirm | Mode::Clear | Mode::Mark | Mode::TextSearch | Mode::Command
+        ) {
+            return Ok(());
+        }
         if self.mode == Mode::Search {
             self.table_state.select(None);
         }
@@ -104,10 +267,11 @@ impl Component for TableDagRuns {
     }
 
     fn update(&mut self, action: Action) -> Result<Option<Action>> {
-        let table_state = if self.mode == Mode::Task {
-            &mut self.table_tasks_state
-        } else {
-            &mut self.table_state
+        let table_state = match self.mode {
+            Mode::Task => &mut self.table_tasks_state,
+            Mode::Pool => &mut self.table_pools_state,
+            Mode::Dag => &mut self.table_dags_state,
+            _ => &mut self.table_state,
         };
         match action {
             Action::Next => {
@@ -115,16 +279,33 @@ impl Component for TableDagRuns {
                     table_state.select(Some(0));
                 } else if let Some(selected_index) = table_state.selected() {
                     if self.mode == Mode::Task {
-                        if selected_index < self.tasks.as_ref().unwrap().task_instances.len() - 1 {
+                        let tasks_len = self
+                            .tasks
+                            .as_ref()
+                            .map_or(0, |tasks| tasks.task_instances.len());
+                        if selected_index + 1 < tasks_len {
                             table_state.select(Some(selected_index + 1));
                         }
-                    } else if let Some(search) = &self.user_search {
-                        let filtered = self.dag_runs.get_dag_runs_rows_filtered(search);
-                        if selected_index < filtered.len() - 1 {
+                    } else if self.mode == Mode::Dag {
+                        if selected_index + 1 < self.dags.dags.len() {
+                            table_state.select(Some(selected_index + 1));
+                        }
+                    } else if self.mode == Mode::Pool {
+                        if selected_index + 1 < self.pools.pools.len() {
+                            table_state.select(Some(selected_index + 1));
+                        }
+                    } else {
+                        let dag_runs_len = if let Some(search) = &self.user_search {
+                            self.dag_runs.filter_runs_by_dag_id(search).len()
+                        } else {
+                            self.dag_runs.dag_runs.len()
+                        };
+                        if selected_index + 1 < dag_runs_len {
                             self.table_state.select(Some(selected_index + 1));
+                        } else if self.dag_runs.has_more() {
+                            // Scrolling past the last loaded dag run fetches the next page
+                            return Ok(Some(Action::LoadDagRunsPage));
                         }
-                    } else if selected_index < self.dag_runs.dag_runs.len() - 1 {
-                        self.table_state.select(Some(selected_index + 1));
                     }
                 }
             }
@@ -151,21 +332,52 @@ impl Component for TableDagRuns {
                 self.handle_mode(Mode::Task)?;
                 self.columns = vec!["OPERATOR", "TASK ID", "TRY NUMBER", "STATE", "DURATION"];
             }
+            Action::Dag => {
+                self.handle_mode(Mode::Dag)?;
+                self.columns = vec![
+                    "DAG ID",
+                    "OWNERS",
+                    "SCHEDULE",
+                    "TAGS",
+                    "NEXT RUN",
+                    "PAUSED",
+                    "IMPORT ERRORS",
+                ];
+            }
+            Action::Pool => {
+                self.handle_mode(Mode::Pool)?;
+                self.columns = vec!["NAME", "SLOTS", "RUNNING", "QUEUED", "OCCUPIED", "OPEN"];
+            }
+            Action::NextMatch | Action::PreviousMatch
+                if matches!(self.mode, Mode::Log | Mode::Code) =>
+            {
+                let text_match = if action == Action::NextMatch {
+                    self.text_search.next_match()
+                } else {
+                    self.text_search.previous_match()
+                };
+                if let Some(text_match) = text_match {
+                    self.scroll_to(text_match.line);
+                }
+            }
+            Action::ToggleLogHeaders if self.mode == Mode::Log => self.toggle_log_headers(),
+            Action::NextLogLevel if self.mode == Mode::Log => self.next_log_level(),
             Action::Up => {
-                if self.position.unwrap() > 0 {
-                    self.position = Some(self.position.unwrap() - 1);
+                if let Some(position @ 1..) = self.position {
+                    self.position = Some(position - 1);
                 }
             }
             Action::Down => {
                 let lines = if self.mode == Mode::Log {
-                    self.log.lines().count()
+                    self.displayed_text().lines().count()
                 } else {
                     self.code.lines().count() + 2
                 };
+                let position = self.position.unwrap_or(0);
                 if lines > self.heigh_lines as usize
-                    && self.position.unwrap() < (lines - self.heigh_lines as usize)
+                    && position < (lines - self.heigh_lines as usize)
                 {
-                    self.position = Some(self.position.unwrap() + 1);
+                    self.position = Some(position + 1);
                 }
             }
             _ => {}
@@ -180,10 +392,14 @@ impl Component for TableDagRuns {
             } else {
                 vec![]
             }
+        } else if self.mode == Mode::Dag {
+            self.dags.get_dags_row()
+        } else if self.mode == Mode::Pool {
+            self.pools.get_pools_row()
         } else if let Some(search) = &self.user_search {
-            self.dag_runs.get_dag_runs_rows_filtered(search)
+            self.dag_runs.get_dag_runs_rows_filtered(search, &self.dags)
         } else {
-            self.dag_runs.get_dag_runs_rows_context()
+            self.dag_runs.get_dag_runs_rows_context(&self.dags)
         };
         // Set the width of the columns
         let widths = self
@@ -194,35 +410,47 @@ impl Component for TableDagRuns {
         let mut title = vec![
             Span::styled(format!(" {:?}(", self.mode), Style::new().light_cyan()),
             Span::styled(
-                if self.mode == Mode::Task {
-                    self.dag_runs.dag_runs[self.table_state.selected().unwrap()]
-                        .dag_run_id
-                        .to_string()
-                } else if self.mode == Mode::Log {
-                    self.tasks.as_ref().unwrap().task_instances
-                        [self.table_tasks_state.selected().unwrap()]
-                    .task_id
-                    .to_string()
-                } else {
-                    String::from("all")
+                // Nothing may be selected yet, e.g. while the tasks are loading
+                match self.mode {
+                    Mode::Task => self
+                        .get_selected_dag_run()
+                        .map_or_else(|| String::from("n/a"), |dag_run| dag_run.dag_run_id.clone()),
+                    Mode::Log => self
+                        .get_selected_task()
+                        .map_or_else(|| String::from("n/a"), |task| task.task_id.clone()),
+                    Mode::DagRun => self.dag_id.clone().unwrap_or_else(|| String::from("all")),
+                    _ => String::from("all"),
                 },
                 Style::new().magenta(),
             ),
             Span::styled(")", Style::new().light_cyan()),
             Span::styled("[", Style::new().white()),
             Span::styled(
-                format!(
-                    "{}",
-                    if self.mode == Mode::Log {
-                        self.try_number as u32
-                    } else {
+                if self.mode == Mode::Log {
+                    self.try_number.to_string()
+                } else if self.mode == Mode::Dag {
+                    self.dags.get_total_entries().to_string()
+                } else if self.mode == Mode::Pool {
+                    self.pools.get_total_entries().to_string()
+                } else if self.mode == Mode::Task {
+                    self.tasks
+                        .as_ref()
+                        .map_or(0, |tasks| tasks.get_total_entries())
+                        .to_string()
+                } else {
+                    format!(
+                        "{}/{}",
+                        self.dag_runs.get_loaded_entries(),
                         self.dag_runs.get_total_entries()
-                    }
-                ),
+                    )
+                },
                 Style::new().light_yellow(),
             ),
             Span::styled("] ", Style::new().white()),
         ];
+        if self.mode == Mode::Log && self.following_log {
+            title.push(Span::styled("<following> ", Style::new().light_green()));
+        }
         if let Some(search) = &self.user_search {
             title.push(Span::raw("<"));
             title.push(Span::styled(
@@ -237,13 +465,68 @@ impl Component for TableDagRuns {
             if self.position.is_none() {
                 self.position = Some(0);
             }
-            let log = Paragraph::new(if self.mode == Mode::Log {
-                Text::raw(&self.log)
+            self.displayed_text();
+            let text = if self.mode == Mode::Log {
+                &self.log_view.text
             } else {
-                Text::raw(&self.code)
-            })
-            .scroll((self.position.unwrap() as u16, 0))
-            .block(
+                &self.code
+            };
+            self.text_search.refresh(self.mode, text);
+            if let Some(level_title) = self.log_view.get_title().filter(|_| self.mode == Mode::Log)
+            {
+                title.push(Span::styled(
+                    format!("<{}> ", level_title),
+                    Style::new().bg(Color::Yellow).fg(Color::Black),
+                ));
+            }
+            if let Some(search_title) = self.text_search.get_title() {
+                title.push(Span::styled(
+                    format!("<{}> ", search_title),
+                    Style::new().bg(Color::Green),
+                ));
+            }
+            // Only the visible lines are styled
+            let lines = if self.mode == Mode::Code {
+                self.syntax_highlight.refresh(text);
+                // The gutter is as wide as the number of the last line
+                let width = text.lines().count().to_string().len();
+                text.lines()
+                    .enumerate()
+                    .skip(self.position.unwrap())
+                    .take(area.height as usize)
+                    .map(|(number, line)| {
+                        let gutter_style = if Some(number) == self.goto_line {
+                            Style::new().light_yellow().bold()
+                        } else {
+                            Style::new().dark_gray()
+                        };
+                        let mut spans = vec![Span::styled(
+                            format!("{:>width$} │ ", number + 1, width = width),
+                            gutter_style,
+                        )];
+                        spans.extend(self.text_search.highlight(
+                            number,
+                            line,
+                            &self.syntax_highlight.get_line_styles(number, line),
+                        ));
+                        Line::from(spans)
+                    })
+                    .collect::<Vec<_>>()
+            } else {
+                text.lines()
+                    .enumerate()
+                    .skip(self.position.unwrap())
+                    .take(area.height as usize)
+                    .map(|(number, line)| {
+                        Line::from(self.text_search.highlight(
+                            number,
+                            line,
+                            &[(0..line.len(), self.log_view.get_line_style(number))],
+                        ))
+                    })
+                    .collect::<Vec<_>>()
+            };
+            let log = Paragraph::new(lines).block(
                 Block::default()
                     .title(Line::from(title))
                     .title_alignment(Alignment::Center)
@@ -267,10 +550,11 @@ impl Component for TableDagRuns {
             f.render_stateful_widget(
                 table,
                 area,
-                if self.mode == Mode::Task {
-                    &mut self.table_tasks_state
-                } else {
-                    &mut self.table_state
+                match self.mode {
+                    Mode::Task => &mut self.table_tasks_state,
+                    Mode::Pool => &mut self.table_pools_state,
+                    Mode::Dag => &mut self.table_dags_state,
+                    _ => &mut self.table_state,
                 },
             );
         }
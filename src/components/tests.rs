@@ -0,0 +1,364 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use strum::IntoEnumIterator;
+
+use super::ascii::Ascii;
+use super::clear_confirm::ClearConfirm;
+use super::confirm::Confirm;
+use super::context_informations::ContextInformation;
+use super::mark_confirm::MarkConfirm;
+use super::shortcut::Shortcut;
+use super::status_bar::StatusBar;
+use super::table_dag_runs::TableDagRuns;
+use super::*;
+use crate::models::clear::ClearRequest;
+use crate::models::dag_runs::DagRuns;
+use crate::models::dags::Dags;
+use crate::models::mark::{MarkRequest, MarkState};
+use crate::models::pools::Pools;
+use crate::models::tasks::Tasks;
+use crate::test_utils::{fixture, fixture_json};
+
+fn config() -> Config {
+    Config {
+        current_context: String::from("prod"),
+        ..Config::defaults()
+    }
+}
+
+/// Draw `component` alone on a `width` x `height` terminal.
+fn render(component: &mut impl Component, width: u16, height: u16) -> TestBackend {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal
+        .draw(|f| component.draw(f, f.size()).unwrap())
+        .unwrap();
+    terminal.backend().clone()
+}
+
+/// The action `component` dispatches when `code` is pressed.
+fn press(component: &mut impl Component, code: KeyCode) -> Option<Action> {
+    component
+        .handle_key_events(KeyEvent::new(code, KeyModifiers::NONE))
+        .unwrap()
+}
+
+/// Modes the status bar shows before `mode`, as the app records them.
+fn breadcrumb(mode: Mode) -> Vec<Mode> {
+    match mode {
+        Mode::Task => vec![Mode::DagRun],
+        Mode::Log => vec![Mode::DagRun, Mode::Task],
+        _ => vec![],
+    }
+}
+
+/// Switch `table` to `mode` as the app does, the modals and command bars leave the view as is.
+fn switch_mode(table: &mut TableDagRuns, mode: Mode) {
+    let action = match mode {
+        Mode::DagRun => Action::DagRun,
+        Mode::Task => Action::Task,
+        Mode::Dag => Action::Dag,
+        Mode::Pool => Action::Pool,
+        mode => return table.handle_mode(mode).unwrap(),
+    };
+    table.update(action).unwrap();
+}
+
+/// The table of the app once every view has been loaded from the fixtures, the first row of
+/// each table being selected.
+fn loaded_table_dag_runs() -> TableDagRuns {
+    let mut table = TableDagRuns::new();
+    table.register_config_handler(config()).unwrap();
+    table.set_dag_runs(fixture_json::<DagRuns>("dag_runs.json"));
+    table.dags = fixture_json::<Dags>("dags.json");
+    table.pools = fixture_json::<Pools>("pools.json");
+    table.tasks = Some(fixture_json::<Tasks>("task_instances.json"));
+    table.set_log(fixture("log.txt"));
+    table.set_code(fixture("dag_source.py"));
+    table.try_number = 2;
+    table.table_state.select(Some(0));
+    table.table_tasks_state.select(Some(1));
+    table.table_dags_state.select(Some(0));
+    table.table_pools_state.select(Some(0));
+    table
+}
+
+#[test]
+fn table_dag_runs() {
+    for mode in Mode::iter() {
+        let mut table = loaded_table_dag_runs();
+        if mode == Mode::Search {
+            table.user_search = Some(String::from("bash"));
+        }
+        switch_mode(&mut table, mode);
+        insta::assert_snapshot!(
+            format!("table_dag_runs_{:?}", mode),
+            render(&mut table, 120, 16)
+        );
+    }
+}
+
+#[test]
+fn table_dag_runs_before_loading() {
+    for mode in Mode::iter() {
+        let mut table = TableDagRuns::new();
+        switch_mode(&mut table, mode);
+        insta::assert_snapshot!(
+            format!("table_dag_runs_before_loading_{:?}", mode),
+            render(&mut table, 120, 6)
+        );
+    }
+}
+
+#[test]
+fn scroll_before_drawing() {
+    for mode in [Mode::Code, Mode::Log] {
+        let mut table = loaded_table_dag_runs();
+        switch_mode(&mut table, mode);
+        table.position = None;
+        table.update(Action::Up).unwrap();
+        assert_eq!(table.position, None);
+        table.update(Action::Down).unwrap();
+        assert_eq!(table.position, Some(1));
+    }
+}
+
+#[test]
+fn status_bar() {
+    for mode in Mode::iter() {
+        let mut status_bar = StatusBar::new();
+        status_bar.mode_breadcrumb = breadcrumb(mode);
+        status_bar.register_mode(mode);
+        insta::assert_snapshot!(
+            format!("status_bar_{:?}", mode),
+            render(&mut status_bar, 60, 1)
+        );
+    }
+}
+
+#[test]
+fn status_bar_message() {
+    let mut status_bar = StatusBar::new();
+    status_bar
+        .update(Action::Error(String::from("Failed to load dag runs")))
+        .unwrap();
+    insta::assert_snapshot!(render(&mut status_bar, 60, 1));
+}
+
+#[test]
+fn shortcut() {
+    for mode in Mode::iter() {
+        let mut shortcut = Shortcut::new();
+        shortcut.register_config_handler(config()).unwrap();
+        shortcut.register_mode(mode);
+        insta::assert_snapshot!(
+            format!("shortcut_{:?}", mode),
+            render(&mut shortcut, 100, 7)
+        );
+    }
+}
+
+#[test]
+fn context_information() {
+    let mut context_information = ContextInformation::new();
+    context_information
+        .register_config_handler(config())
+        .unwrap();
+    context_information.set_airflow_version("2.8.3");
+    context_information.register_context_information(&fixture_json::<DagRuns>("dag_runs.json"));
+    insta::assert_snapshot!(render(&mut context_information, 50, 7));
+}
+
+#[test]
+fn ascii() {
+    insta::assert_snapshot!(render(&mut Ascii::new(), 22, 7));
+}
+
+#[test]
+fn confirm_keys() {
+    let mut confirm = Confirm::new();
+    confirm.ask(
+        String::from("Pause dag etl ?"),
+        Action::SetDagPaused(String::from("etl"), true),
+    );
+    assert_eq!(press(&mut confirm, KeyCode::Char('y')), None);
+
+    confirm.handle_mode(Mode::Confirm).unwrap();
+    assert_eq!(
+        press(&mut confirm, KeyCode::Char('y')),
+        Some(Action::Accept)
+    );
+    assert_eq!(press(&mut confirm, KeyCode::Enter), Some(Action::Accept));
+    assert_eq!(
+        press(&mut confirm, KeyCode::Char('n')),
+        Some(Action::Cancel)
+    );
+    assert_eq!(press(&mut confirm, KeyCode::Esc), Some(Action::Cancel));
+    assert_eq!(press(&mut confirm, KeyCode::Char('x')), None);
+    assert_eq!(
+        confirm.take_action(),
+        Some(Action::SetDagPaused(String::from("etl"), true))
+    );
+}
+
+#[test]
+fn selected_dag_run_of_a_filtered_table() {
+    let mut table = loaded_table_dag_runs();
+    let mut other_dag_run = table.dag_runs.dag_runs[0].clone();
+    other_dag_run.dag_id = String::from("example_python_operator");
+    table.dag_runs.dag_runs.insert(0, other_dag_run);
+    table.user_search = Some(String::from("bash"));
+    table.table_state.select(Some(1));
+    assert_eq!(
+        table
+            .get_selected_dag_run()
+            .map(|dag_run| dag_run.dag_run_id.as_str()),
+        Some("manual__2024-03-10T14:22:51.052366+00:00")
+    );
+
+    table.table_state.select(Some(2));
+    assert_eq!(table.get_selected_dag_run(), None);
+}
+
+#[test]
+fn clear_confirm_keys() {
+    let request = ClearRequest::task("etl", "manual__1", "load");
+    let mut clear_confirm = ClearConfirm::new();
+    clear_confirm.open(request.clone());
+    clear_confirm.handle_mode(Mode::Clear).unwrap();
+    // Nothing is cleared before the preview is loaded
+    assert_eq!(press(&mut clear_confirm, KeyCode::Char('y')), None);
+
+    let mut upstream = request.clone();
+    upstream.options.include_upstream = true;
+    assert_eq!(
+        press(&mut clear_confirm, KeyCode::Char('u')),
+        Some(Action::PreviewClear(Box::new(upstream.clone())))
+    );
+    // The preview of the previous options is ignored
+    clear_confirm.set_preview(&request, vec![]);
+    assert_eq!(press(&mut clear_confirm, KeyCode::Enter), None);
+    clear_confirm.set_preview(&upstream, vec![]);
+    assert_eq!(
+        press(&mut clear_confirm, KeyCode::Enter),
+        Some(Action::ApplyClear(Box::new(upstream)))
+    );
+    assert_eq!(
+        press(&mut clear_confirm, KeyCode::Esc),
+        Some(Action::Cancel)
+    );
+
+    clear_confirm.handle_mode(Mode::Task).unwrap();
+    assert_eq!(press(&mut clear_confirm, KeyCode::Char('n')), None);
+}
+
+#[test]
+fn clear_confirm_retries_a_failed_preview() {
+    let request = ClearRequest::task("etl", "manual__1", "load");
+    let mut clear_confirm = ClearConfirm::new();
+    clear_confirm.open(request.clone());
+    clear_confirm.handle_mode(Mode::Clear).unwrap();
+    // Nothing to retry while the preview is loading
+    assert_eq!(press(&mut clear_confirm, KeyCode::Char('r')), None);
+
+    clear_confirm.set_preview_failed(&request, String::from("Failed to preview clear"));
+    assert!(render(&mut clear_confirm, 80, 10)
+        .to_string()
+        .contains("Failed to preview clear"));
+    assert_eq!(press(&mut clear_confirm, KeyCode::Enter), None);
+    assert_eq!(
+        press(&mut clear_confirm, KeyCode::Char('r')),
+        Some(Action::PreviewClear(Box::new(request.clone())))
+    );
+    assert!(render(&mut clear_confirm, 80, 10)
+        .to_string()
+        .contains("Loading..."));
+
+    clear_confirm.set_preview(&request, vec![]);
+    assert_eq!(
+        press(&mut clear_confirm, KeyCode::Enter),
+        Some(Action::ApplyClear(Box::new(request)))
+    );
+}
+
+#[test]
+fn mark_confirm_keys() {
+    let request = MarkRequest::task("etl", "manual__1", "load", MarkState::Failed);
+    let mut mark_confirm = MarkConfirm::new();
+    mark_confirm.open(request.clone());
+    mark_confirm.handle_mode(Mode::Mark).unwrap();
+    assert_eq!(press(&mut mark_confirm, KeyCode::Char('u')), None);
+    assert_eq!(press(&mut mark_confirm, KeyCode::Char('d')), None);
+    assert_eq!(press(&mut mark_confirm, KeyCode::Char('d')), None);
+    let upstream = MarkRequest {
+        include_upstream: true,
+        ..request.clone()
+    };
+    assert_eq!(
+        press(&mut mark_confirm, KeyCode::Char('y')),
+        Some(Action::ApplyMark(Box::new(upstream.clone())))
+    );
+    assert_eq!(
+        press(&mut mark_confirm, KeyCode::Enter),
+        Some(Action::ApplyMark(Box::new(upstream)))
+    );
+    assert_eq!(
+        press(&mut mark_confirm, KeyCode::Char('n')),
+        Some(Action::Cancel)
+    );
+    assert_eq!(press(&mut mark_confirm, KeyCode::Esc), Some(Action::Cancel));
+    insta::assert_snapshot!(render(&mut mark_confirm, 80, 9));
+
+    // The upstream and downstream tasks are only marked along with a task instance
+    let request = MarkRequest::dag_run("etl", "manual__1", MarkState::Success);
+    mark_confirm.open(request.clone());
+    assert_eq!(press(&mut mark_confirm, KeyCode::Char('u')), None);
+    assert_eq!(
+        press(&mut mark_confirm, KeyCode::Char('y')),
+        Some(Action::ApplyMark(Box::new(request)))
+    );
+
+    mark_confirm.close();
+    assert_eq!(press(&mut mark_confirm, KeyCode::Char('y')), None);
+}
+
+#[test]
+fn highlight_another_source_code_of_the_same_length() {
+    let mut table = loaded_table_dag_runs();
+    switch_mode(&mut table, Mode::Code);
+    table.set_code(String::from("import os\nimport re\n"));
+    render(&mut table, 60, 6);
+    // The ranges highlighted in the previous source code split the `é`
+    table.set_code(String::from("a = 'éé'\nb = 'xy'\n"));
+    insta::assert_snapshot!(render(&mut table, 60, 6));
+}
+
+#[test]
+fn search_another_source_code_of_the_same_length() {
+    let mut table = loaded_table_dag_runs();
+    switch_mode(&mut table, Mode::Code);
+    table.set_code(String::from("import os\nimport re\n"));
+    table.search_text("import");
+    render(&mut table, 60, 6);
+    assert_eq!(
+        table.text_search.get_title().as_deref(),
+        Some("/import 1/2")
+    );
+    // The matches in the previous source code split the `é`
+    table.set_code(String::from("a = 'éé'\nb = 'xy'\n"));
+    render(&mut table, 60, 6);
+    assert_eq!(
+        table.text_search.get_title().as_deref(),
+        Some("/import 0/0")
+    );
+}
+
+#[test]
+fn display_another_log_of_the_same_length() {
+    let mut table = loaded_table_dag_runs();
+    switch_mode(&mut table, Mode::Log);
+    table.set_log(String::from("[2024-03-12] {dag.py:1} INFO - first\n"));
+    render(&mut table, 60, 4);
+    table.set_log(String::from("[2024-03-12] {dag.py:1} INFO - again\n"));
+    insta::assert_snapshot!(render(&mut table, 60, 4));
+}
@@ -0,0 +1,179 @@
+use std::ops::Range;
+
+use ratatui::prelude::*;
+use regex::Regex;
+
+use crate::mode::Mode;
+
+/// A match of the search, as a byte range of a line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextMatch {
+    pub line: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Regex search inside the log and source code views.
+/// Matches are computed again whenever the searched text grows, e.g. a followed log, and once
+/// invalidated when another text is loaded.
+#[derive(Debug, Default)]
+pub struct TextSearch {
+    pattern: Option<String>,
+    regex: Option<Regex>,
+    error: bool,
+    matches: Vec<TextMatch>,
+    current: Option<usize>,
+    // Mode and length of the text the matches were computed on
+    searched: Option<(Mode, usize)>,
+}
+
+impl TextSearch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Search `pattern`, an empty pattern ends the search.
+    /// An invalid regex keeps the previous matches until it is fixed.
+    pub fn set_pattern(&mut self, pattern: &str) {
+        if pattern.is_empty() {
+            *self = Self::default();
+            return;
+        }
+        self.pattern = Some(pattern.to_string());
+        match Regex::new(pattern) {
+            Ok(regex) => {
+                self.regex = Some(regex);
+                self.error = false;
+                self.current = None;
+                self.searched = None;
+            }
+            Err(_) => self.error = true,
+        }
+    }
+
+    /// Compute the matches again on the next refresh, the searched text changed.
+    pub fn invalidate(&mut self) {
+        self.searched = None;
+    }
+
+    /// Compute the matches of `text` displayed in `mode`, unless it was already searched.
+    pub fn refresh(&mut self, mode: Mode, text: &str) {
+        if self.searched == Some((mode, text.len())) {
+            return;
+        }
+        self.searched = Some((mode, text.len()));
+        self.matches.clear();
+        let Some(regex) = &self.regex else {
+            self.current = None;
+            return;
+        };
+        for (line, content) in text.lines().enumerate() {
+            self.matches
+                .extend(
+                    regex
+                        .find_iter(content)
+                        .filter(|m| !m.is_empty())
+                        .map(|m| TextMatch {
+                            line,
+                            start: m.start(),
+                            end: m.end(),
+                        }),
+                );
+        }
+        self.current = match self.current {
+            _ if self.matches.is_empty() => None,
+            Some(current) => Some(current.min(self.matches.len() - 1)),
+            None => Some(0),
+        };
+    }
+
+    /// Select the first match at or after `line`, wrapping to the first match.
+    pub fn select_from(&mut self, line: usize) -> Option<TextMatch> {
+        if self.matches.is_empty() {
+            return None;
+        }
+        let index = self
+            .matches
+            .iter()
+            .position(|m| m.line >= line)
+            .unwrap_or(0);
+        self.current = Some(index);
+        Some(self.matches[index])
+    }
+
+    /// Select the next match, wrapping to the first one.
+    pub fn next_match(&mut self) -> Option<TextMatch> {
+        let current = self.current?;
+        let index = (current + 1) % self.matches.len();
+        self.current = Some(index);
+        Some(self.matches[index])
+    }
+
+    /// Select the previous match, wrapping to the last one.
+    pub fn previous_match(&mut self) -> Option<TextMatch> {
+        let current = self.current?;
+        let index = current.checked_sub(1).unwrap_or(self.matches.len() - 1);
+        self.current = Some(index);
+        Some(self.matches[index])
+    }
+
+    /// The matches of `line`, along with the index of the first one.
+    fn line_matches(&self, line: usize) -> (usize, &[TextMatch]) {
+        let start = self.matches.partition_point(|m| m.line < line);
+        let end = self.matches.partition_point(|m| m.line <= line);
+        (start, &self.matches[start..end])
+    }
+
+    /// Split `content`, the line number `line`, in spans of the given `styles` covering the
+    /// line, highlighting its matches over them.
+    pub fn highlight<'a>(
+        &self,
+        line: usize,
+        content: &'a str,
+        styles: &[(Range<usize>, Style)],
+    ) -> Vec<Span<'a>> {
+        let (first, matches) = self.line_matches(line);
+        let mut spans = vec![];
+        for (range, style) in styles {
+            let mut position = range.start;
+            for (index, m) in (first..).zip(matches) {
+                if m.end <= position || m.start >= range.end {
+                    continue;
+                }
+                if m.start > position {
+                    spans.push(Span::styled(&content[position..m.start], *style));
+                }
+                let match_style = if Some(index) == self.current {
+                    Style::new().black().on_light_yellow().bold()
+                } else {
+                    Style::new().black().on_yellow()
+                };
+                let end = m.end.min(range.end);
+                spans.push(Span::styled(
+                    &content[position.max(m.start)..end],
+                    match_style,
+                ));
+                position = end;
+            }
+            if position < range.end {
+                spans.push(Span::styled(&content[position..range.end], *style));
+            }
+        }
+        spans
+    }
+
+    /// Counter displayed in the title of the view, e.g. `/error 3/42`.
+    pub fn get_title(&self) -> Option<String> {
+        let pattern = self.pattern.as_ref()?;
+        Some(if self.error {
+            format!("/{} invalid regex", pattern)
+        } else {
+            format!(
+                "/{} {}/{}",
+                pattern,
+                self.current.map_or(0, |current| current + 1),
+                self.matches.len()
+            )
+        })
+    }
+}
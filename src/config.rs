@@ -12,9 +12,24 @@ use serde::{
 };
 use serde_json::Value as JsonValue;
 
+use strum::{IntoEnumIterator, VariantNames};
+
 use crate::{action::Action, mode::Mode};
 
-#[derive(Clone, Debug, Deserialize, Default)]
+pub mod check;
+
+// Defaults of every setting, a user file only needs to hold what it changes
+const CONFIG: &str = include_str!("../.config/config.json5");
+// Files read from the configuration directory, merged in this order
+pub(crate) const CONFIG_FILES: [(&str, config::FileFormat); 5] = [
+    ("config.json5", config::FileFormat::Json5),
+    ("config.json", config::FileFormat::Json),
+    ("config.yaml", config::FileFormat::Yaml),
+    ("config.toml", config::FileFormat::Toml),
+    ("config.ini", config::FileFormat::Ini),
+];
+
+#[derive(Clone, Debug, Deserialize, Default, PartialEq)]
 pub struct AppConfig {
     #[serde(default)]
     pub _data_dir: PathBuf,
@@ -22,7 +37,7 @@ pub struct AppConfig {
     pub _config_dir: PathBuf,
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
 pub struct Config {
     #[serde(default, flatten)]
     pub config: AppConfig,
@@ -31,6 +46,16 @@ pub struct Config {
     #[serde(default)]
     pub airflow: Airflow,
     #[serde(default)]
+    pub contexts: Vec<Context>,
+    #[serde(default)]
+    pub default_context: Option<String>,
+    #[serde(skip)]
+    pub current_context: String,
+    #[serde(default)]
+    pub page_size: Option<u32>,
+    #[serde(default)]
+    pub export_dir: Option<PathBuf>,
+    #[serde(default)]
     pub styles: Styles,
 }
 
@@ -42,15 +67,8 @@ impl Config {
             .set_default("_data_dir", data_dir.to_str().unwrap())?
             .set_default("_config_dir", config_dir.to_str().unwrap())?;
 
-        let config_files = [
-            ("config.json5", config::FileFormat::Json5),
-            ("config.json", config::FileFormat::Json),
-            ("config.yaml", config::FileFormat::Yaml),
-            ("config.toml", config::FileFormat::Toml),
-            ("config.ini", config::FileFormat::Ini),
-        ];
         let mut found_config = false;
-        for (file, format) in &config_files {
+        for (file, format) in &CONFIG_FILES {
             builder = builder.add_source(
                 config::File::from(config_dir.join(file))
                     .format(*format)
@@ -61,16 +79,97 @@ impl Config {
             }
         }
         if !found_config {
-            log::error!("No configuration file found. Application may not behave as expected");
+            log::info!("No configuration file found, the default configuration is used");
         }
 
-        let cfg: Self = builder.build()?.try_deserialize()?;
+        let mut cfg: Self = builder.build()?.try_deserialize()?;
+        cfg.merge_defaults(Self::defaults());
+        // Without any context declared, the `airflow` section is the only environment
+        cfg.current_context = String::from(DEFAULT_CONTEXT);
 
         Ok(cfg)
     }
+
+    /// The configuration compiled into arvz.
+    pub fn defaults() -> Self {
+        json5::from_str(CONFIG).expect("the default configuration is valid")
+    }
+
+    /// Complete the keybindings and styles of each mode with the ones of `defaults` the user did
+    /// not set.
+    fn merge_defaults(&mut self, defaults: Config) {
+        for (mode, default_bindings) in defaults.keybindings.0 {
+            let user_bindings = self.keybindings.entry(mode).or_default();
+            for (key, action) in default_bindings {
+                user_bindings.entry(key).or_insert(action);
+            }
+        }
+        for (mode, default_styles) in defaults.styles.0 {
+            let user_styles = self.styles.entry(mode).or_default();
+            for (name, style) in default_styles {
+                user_styles.entry(name).or_insert(style);
+            }
+        }
+    }
+
+    /// Make the context named `name` the active one, its airflow settings replace `airflow`.
+    pub fn use_context(&mut self, name: &str) -> Result<(), String> {
+        let Some(context) = self.contexts.iter().find(|context| context.name == name) else {
+            return Err(format!(
+                "Unknown context `{}`, available contexts: {}",
+                name,
+                self.get_context_names().join(", ")
+            ));
+        };
+        let mut airflow = context.airflow.clone();
+        airflow
+            .resolve_secrets()
+            .map_err(|e| format!("Context `{}`: {}", name, e))?;
+        self.airflow = airflow;
+        self.current_context = context.name.clone();
+        Ok(())
+    }
+
+    /// Activate the context to start with: `name` if given, then `default_context`,
+    /// then the first declared context.
+    pub fn use_initial_context(&mut self, name: Option<&str>) -> Result<(), String> {
+        let name = name
+            .map(String::from)
+            .or_else(|| self.default_context.clone())
+            .or_else(|| self.contexts.first().map(|context| context.name.clone()));
+        match name {
+            Some(name) => self.use_context(&name),
+            None => self.airflow.resolve_secrets(),
+        }
+    }
+
+    /// Number of dag runs fetched per request.
+    pub fn get_page_size(&self) -> u32 {
+        self.page_size.unwrap_or(DEFAULT_PAGE_SIZE).max(1)
+    }
+
+    /// Directory the logs are exported to when no directory is given, the working directory by
+    /// default.
+    pub fn get_export_dir(&self) -> PathBuf {
+        self.export_dir
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    pub fn get_context_names(&self) -> Vec<&str> {
+        self.contexts
+            .iter()
+            .map(|context| context.name.as_str())
+            .collect()
+    }
 }
 
-#[derive(Clone, Debug, Default, Deref, DerefMut)]
+// Name of the context built from the `airflow` section
+const DEFAULT_CONTEXT: &str = "default";
+// Airflow's default `maximum_page_limit`
+const DEFAULT_PAGE_SIZE: u32 = 100;
+
+#[derive(Clone, Debug, Default, Deref, DerefMut, PartialEq)]
 pub struct KeyBindings(pub HashMap<Mode, HashMap<Vec<KeyEvent>, Action>>);
 
 impl<'de> Deserialize<'de> for KeyBindings {
@@ -78,28 +177,226 @@ impl<'de> Deserialize<'de> for KeyBindings {
     where
         D: Deserializer<'de>,
     {
-        let parsed_map = HashMap::<Mode, HashMap<String, Action>>::deserialize(deserializer)?;
-
-        let keybindings = parsed_map
-            .into_iter()
-            .map(|(mode, inner_map)| {
-                let converted_inner_map = inner_map
-                    .into_iter()
-                    .map(|(key_str, cmd)| (parse_key_sequence(&key_str).unwrap(), cmd))
-                    .collect();
-                (mode, converted_inner_map)
-            })
-            .collect();
+        let parsed_map = HashMap::<String, HashMap<String, JsonValue>>::deserialize(deserializer)?;
+
+        // A mistake is reported with its mode and key, `arvz check-config` reports all of them
+        let mut keybindings = HashMap::new();
+        for (mode_str, inner_map) in parsed_map {
+            let mode = parse_mode(&mode_str)
+                .map_err(|e| de::Error::custom(format!("keybindings.{}: {}", mode_str, e)))?;
+            let mut converted_inner_map = HashMap::new();
+            for (key_str, action) in inner_map {
+                let binding =
+                    parse_key_sequence(&key_str).and_then(|key| Ok((key, parse_action(&action)?)));
+                let (key, action) = binding.map_err(|e| {
+                    de::Error::custom(format!("keybindings.{}.{}: {}", mode_str, key_str, e))
+                })?;
+                converted_inner_map.insert(key, action);
+            }
+            keybindings.insert(mode, converted_inner_map);
+        }
 
         Ok(KeyBindings(keybindings))
     }
 }
 
-#[derive(Clone, Debug, Default, Deserialize)]
+/// The mode named `name`, in any case as the configuration files have their keys lowercased.
+pub(crate) fn parse_mode(name: &str) -> Result<Mode, String> {
+    Mode::iter()
+        .find(|mode| format!("{:?}", mode).eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            let modes = Mode::iter()
+                .map(|mode| format!("{:?}", mode))
+                .collect::<Vec<_>>();
+            unknown(
+                "mode",
+                name,
+                &modes.iter().map(String::as_str).collect::<Vec<_>>(),
+            )
+        })
+}
+
+/// The action bound to a key, its name or, for an action with an argument, an object such as
+/// `{ "Runs": "my_dag" }`.
+pub(crate) fn parse_action(value: &JsonValue) -> Result<Action, String> {
+    let (name, argument) = match value {
+        JsonValue::String(name) => (name.as_str(), None),
+        JsonValue::Object(object) if object.len() == 1 => {
+            let (name, argument) = object.iter().next().unwrap();
+            (name.as_str(), Some(argument))
+        }
+        _ => return Err(format!("`{}` is not an action", value)),
+    };
+    // The name of an action with an argument is a key, lowercased as the other keys
+    let Some(variant) = Action::VARIANTS
+        .iter()
+        .find(|variant| variant.eq_ignore_ascii_case(name))
+    else {
+        return Err(unknown("action", name, Action::VARIANTS));
+    };
+    let value = match argument {
+        Some(argument) => serde_json::json!({ *variant: argument }),
+        None => JsonValue::from(*variant),
+    };
+    serde_json::from_value(value).map_err(|e| format!("Action `{}`: {}", variant, e))
+}
+
+/// Error of an unknown `name`, suggesting the closest of `names` in case of a typo.
+fn unknown(what: &str, name: &str, names: &[&str]) -> String {
+    let closest = names
+        .iter()
+        .map(|candidate| {
+            (
+                edit_distance(&name.to_lowercase(), &candidate.to_lowercase()),
+                candidate,
+            )
+        })
+        .min();
+    match closest {
+        Some((distance, candidate)) if distance <= 2 => {
+            format!("Unknown {} `{}`, did you mean `{}`?", what, name, candidate)
+        }
+        _ => format!("Unknown {} `{}`", what, name),
+    }
+}
+
+/// Number of characters to insert, delete or substitute to turn `a` into `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// A named Airflow environment, e.g. `dev`, `staging` or `prod`.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Context {
+    pub name: String,
+    #[serde(flatten)]
+    pub airflow: Airflow,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
 pub struct Airflow {
+    #[serde(default)]
     pub username: String,
-    pub password: String,
+    #[serde(default)]
+    pub password: Secret,
     pub host: String,
+    #[serde(default)]
+    pub auth: Auth,
+    /// Headers added to every request, e.g. the ones expected by an authenticating proxy
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    #[serde(default)]
+    pub tls: Tls,
+}
+
+/// TLS settings of the connections to Airflow, the system CA certificates are always trusted.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct Tls {
+    /// PEM bundle of the additional CA certificates to trust, e.g. a corporate CA
+    pub ca_file: Option<PathBuf>,
+    /// PEM certificate presented to the webserver for mutual TLS, with `client_key`
+    pub client_cert: Option<PathBuf>,
+    /// PEM private key of `client_cert`
+    pub client_key: Option<PathBuf>,
+    /// Accept any certificate, only meant for a local webserver with a self-signed one
+    #[serde(default)]
+    pub insecure_skip_verify: bool,
+}
+
+/// How the requests to Airflow are authenticated.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Auth {
+    /// HTTP basic auth with `username` and `password`
+    #[default]
+    Basic,
+    /// `Authorization: Bearer` with a fixed token
+    Bearer { token: Secret },
+    /// `Authorization: Bearer` with the token printed by a shell command, e.g.
+    /// `gcloud auth print-access-token`. The command runs again when the token expires.
+    Command { command: String },
+    /// No authentication, e.g. when `headers` are enough
+    None,
+}
+
+impl Airflow {
+    /// Read the credentials kept out of the configuration file, once the context is used.
+    pub fn resolve_secrets(&mut self) -> Result<(), String> {
+        self.password
+            .resolve()
+            .map_err(|e| format!("password: {}", e))?;
+        if let Auth::Bearer { token } = &mut self.auth {
+            token.resolve().map_err(|e| format!("token: {}", e))?;
+        }
+        Ok(())
+    }
+}
+
+/// A password or a token, written in the configuration file or read from another source:
+/// `{ env: "AIRFLOW_PASSWORD" }`, `{ file: "/var/run/secrets/airflow/password" }` or
+/// `{ command: "pass show airflow" }`.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum Secret {
+    Plain(String),
+    Env { env: String },
+    File { file: PathBuf },
+    Command { command: String },
+}
+
+impl Default for Secret {
+    fn default() -> Self {
+        Secret::Plain(String::new())
+    }
+}
+
+impl Secret {
+    /// Read the secret from its source, it is then kept as a plain value.
+    pub fn resolve(&mut self) -> Result<(), String> {
+        let value = match self {
+            Secret::Plain(_) => return Ok(()),
+            Secret::Env { env } => std::env::var(&*env)
+                .map_err(|_| format!("environment variable `{}` is not set", env))?,
+            Secret::File { file } => std::fs::read_to_string(&*file)
+                .map_err(|e| format!("cannot read {}: {}", file.display(), e))?,
+            Secret::Command { command } => {
+                let output = std::process::Command::new("sh")
+                    .arg("-c")
+                    .arg(&*command)
+                    .output()
+                    .map_err(|e| format!("cannot run `{}`: {}", command, e))?;
+                if !output.status.success() {
+                    return Err(format!(
+                        "`{}` failed: {}",
+                        command,
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    ));
+                }
+                String::from_utf8_lossy(&output.stdout).into_owned()
+            }
+        };
+        // Files and commands usually end with a newline that is not part of the secret
+        *self = Secret::Plain(value.trim_end_matches(['\r', '\n']).to_string());
+        Ok(())
+    }
+
+    /// The plain value, empty until the secret is resolved.
+    pub fn expose(&self) -> &str {
+        match self {
+            Secret::Plain(value) => value,
+            _ => "",
+        }
+    }
 }
 
 fn parse_key_event(raw: &str) -> Result<KeyEvent, String> {
@@ -273,7 +570,7 @@ pub fn parse_key_sequence(raw: &str) -> Result<Vec<KeyEvent>, String> {
     sequences.into_iter().map(parse_key_event).collect()
 }
 
-#[derive(Clone, Debug, Default, Deref, DerefMut)]
+#[derive(Clone, Debug, Default, Deref, DerefMut, PartialEq)]
 pub struct Styles(pub HashMap<Mode, HashMap<String, Style>>);
 
 impl<'de> Deserialize<'de> for Styles {
@@ -281,18 +578,18 @@ impl<'de> Deserialize<'de> for Styles {
     where
         D: Deserializer<'de>,
     {
-        let parsed_map = HashMap::<Mode, HashMap<String, String>>::deserialize(deserializer)?;
-
-        let styles = parsed_map
-            .into_iter()
-            .map(|(mode, inner_map)| {
-                let converted_inner_map = inner_map
-                    .into_iter()
-                    .map(|(str, style)| (str, parse_style(&style)))
-                    .collect();
-                (mode, converted_inner_map)
-            })
-            .collect();
+        let parsed_map = HashMap::<String, HashMap<String, String>>::deserialize(deserializer)?;
+
+        let mut styles = HashMap::new();
+        for (mode_str, inner_map) in parsed_map {
+            let mode = parse_mode(&mode_str)
+                .map_err(|e| de::Error::custom(format!("styles.{}: {}", mode_str, e)))?;
+            let converted_inner_map = inner_map
+                .into_iter()
+                .map(|(str, style)| (str, parse_style(&style)))
+                .collect();
+            styles.insert(mode, converted_inner_map);
+        }
 
         Ok(Styles(styles))
     }
@@ -401,3 +698,6 @@ fn parse_color(s: &str) -> Option<Color> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests;
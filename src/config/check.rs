@@ -0,0 +1,435 @@
+use std::fmt;
+use std::path::Path;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Serialize;
+use serde_json::Value as JsonValue;
+
+use super::{
+    key_event_to_string, parse_action, parse_key_sequence, parse_mode, Airflow, Config,
+    CONFIG_FILES,
+};
+use crate::action::Action;
+use crate::mode::Mode;
+
+// Modes reading the characters typed by the user, a plain character bound there is never typed
+const TEXT_INPUT_MODES: [Mode; 6] = [
+    Mode::Search,
+    Mode::Command,
+    Mode::TextSearch,
+    Mode::Confirm,
+    Mode::Clear,
+    Mode::Mark,
+];
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, strum::Display)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+/// A problem of a configuration file, located by the path of the setting in the file,
+/// e.g. `keybindings.dagrun.<x>`.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub file: String,
+    pub location: String,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.severity, self.file)?;
+        if !self.location.is_empty() {
+            write!(f, ": {}", self.location)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// Collects the diagnostics of a file, or of several files read as one.
+struct Report<'a> {
+    file: &'a str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Report<'_> {
+    fn add(&mut self, severity: Severity, location: &str, message: impl Into<String>) {
+        let file = self.file;
+        self.add_in(file, severity, location, message);
+    }
+
+    /// Add a diagnostic of `file`, one of the files read as one.
+    fn add_in(
+        &mut self,
+        file: &str,
+        severity: Severity,
+        location: &str,
+        message: impl Into<String>,
+    ) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            file: file.to_string(),
+            location: location.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+}
+
+/// Check the configuration files of `dir`, merged as `Config::new` merges them: with their
+/// keys lowercased and the later files overriding the earlier ones.
+pub fn check_config_dir(dir: &Path) -> Vec<Diagnostic> {
+    let dir_name = dir.display().to_string();
+    let mut report = Report {
+        file: &dir_name,
+        diagnostics: vec![],
+    };
+    let mut files: Vec<(String, JsonValue)> = vec![];
+    let mut builder = config::Config::builder();
+    let mut found_config = false;
+    for (name, format) in CONFIG_FILES {
+        let path = dir.join(name);
+        if !path.exists() {
+            continue;
+        }
+        found_config = true;
+        let file = path.display().to_string();
+        let source = config::File::from(path.as_path()).format(format);
+        // Each file is read alone first, for its syntax errors to be located
+        match read_sources(config::Config::builder().add_source(source.clone())) {
+            Ok(value) => {
+                builder = builder.add_source(source);
+                files.push((file, value));
+            }
+            Err(e) => report.add_in(&file, Severity::Error, "", e.to_string()),
+        }
+    }
+    if !found_config {
+        report.add(
+            Severity::Error,
+            "",
+            "No configuration file, one of config.json5, config.json, config.yaml, config.toml \
+             or config.ini is expected",
+        );
+        return report.diagnostics;
+    }
+    // A file which cannot be read is already reported
+    if files.is_empty() {
+        return report.diagnostics;
+    }
+
+    // The settings of several files are located in the directory
+    if let [(file, _)] = files.as_slice() {
+        report.file = file;
+    }
+    let merged = match read_sources(builder) {
+        Ok(merged) => merged,
+        Err(e) => {
+            report.add(Severity::Error, "", e.to_string());
+            return report.diagnostics;
+        }
+    };
+    let has_airflow = match check_value(&mut report, &files, &merged) {
+        Some(config) => !config.airflow.host.is_empty() || !config.contexts.is_empty(),
+        None => true,
+    };
+    if !has_airflow {
+        report.add(
+            Severity::Error,
+            "",
+            "No Airflow webserver configured, set `airflow.host` or declare `contexts`",
+        );
+    }
+    report.diagnostics
+}
+
+/// The settings of the sources of `builder`, as deserialized by `Config::new`.
+fn read_sources(
+    builder: config::ConfigBuilder<config::builder::DefaultState>,
+) -> Result<JsonValue, config::ConfigError> {
+    builder.build()?.try_deserialize::<JsonValue>()
+}
+
+/// Check the settings `merged` from `files`, returning the configuration they hold if it can
+/// be read. The keybindings are checked file by file, for each binding to be located in its
+/// file.
+fn check_value(
+    report: &mut Report<'_>,
+    files: &[(String, JsonValue)],
+    merged: &JsonValue,
+) -> Option<Config> {
+    check_keybindings(report, files);
+    // The other mistakes are reported by serde, without the keybindings already reported
+    let mut merged = merged.clone();
+    if report.has_errors() {
+        if let Some(object) = merged.as_object_mut() {
+            object.remove("keybindings");
+        }
+    }
+    match serde_json::from_value::<Config>(merged) {
+        Ok(config) => {
+            check_contexts(report, &config);
+            Some(config)
+        }
+        Err(e) => {
+            report.add(Severity::Error, "", e.to_string());
+            None
+        }
+    }
+}
+
+/// A key bound in a configuration file.
+struct Binding<'a> {
+    file: &'a str,
+    location: String,
+    key_str: &'a str,
+    mode: Mode,
+    key: Vec<KeyEvent>,
+    action: Action,
+}
+
+fn check_keybindings(report: &mut Report<'_>, files: &[(String, JsonValue)]) {
+    // In the order the files are merged, for the diagnostics to be reported in this order
+    let mut bindings: Vec<Binding<'_>> = vec![];
+    for (file, value) in files {
+        let Some(keybindings) = value.get("keybindings") else {
+            continue;
+        };
+        let Some(keybindings) = keybindings.as_object() else {
+            report.add_in(
+                file,
+                Severity::Error,
+                "keybindings",
+                "Expected the keybindings of each mode",
+            );
+            continue;
+        };
+        for (mode_str, mode_bindings) in keybindings {
+            let location = format!("keybindings.{}", mode_str);
+            let mode = match parse_mode(mode_str) {
+                Ok(mode) => mode,
+                Err(e) => {
+                    report.add_in(file, Severity::Error, &location, e);
+                    continue;
+                }
+            };
+            let Some(mode_bindings) = mode_bindings.as_object() else {
+                report.add_in(
+                    file,
+                    Severity::Error,
+                    &location,
+                    "Expected the actions bound to each key",
+                );
+                continue;
+            };
+            for (key_str, action) in mode_bindings {
+                let location = format!("keybindings.{}.{}", mode_str, key_str);
+                let binding =
+                    parse_key_sequence(key_str).and_then(|key| Ok((key, parse_action(action)?)));
+                let (key, action) = match binding {
+                    Ok(binding) => binding,
+                    Err(e) => {
+                        report.add_in(file, Severity::Error, &location, e);
+                        continue;
+                    }
+                };
+                // A key has several spellings, e.g. `<ctrl-alt-x>` and `<alt-ctrl-x>`, and can
+                // be bound again by a later file
+                let other = bindings
+                    .iter()
+                    .find(|other| other.mode == mode && other.key == key);
+                if let Some(other) = other {
+                    let other_str = if other.file == file {
+                        format!("`{}`", other.key_str)
+                    } else {
+                        format!("`{}` in {}", other.key_str, other.file)
+                    };
+                    if other.action != action {
+                        report.add_in(
+                            file,
+                            Severity::Error,
+                            &location,
+                            format!(
+                                "Same key as {}, bound to {} instead of {}",
+                                other_str, other.action, action
+                            ),
+                        );
+                    } else {
+                        report.add_in(
+                            file,
+                            Severity::Warning,
+                            &location,
+                            format!("Same key and action as {}", other_str),
+                        );
+                    }
+                }
+                if TEXT_INPUT_MODES.contains(&mode) && is_typed(&key) {
+                    report.add_in(
+                        file,
+                        Severity::Warning,
+                        &location,
+                        format!(
+                            "The key can no longer be typed in {:?} mode, prefer a key with ctrl \
+                             or alt",
+                            mode
+                        ),
+                    );
+                }
+                bindings.push(Binding {
+                    file,
+                    location,
+                    key_str,
+                    mode,
+                    key,
+                    action,
+                });
+            }
+        }
+    }
+
+    // The keymap of each mode once every file is merged over the defaults
+    let defaults = Config::defaults().keybindings;
+    let mut keymaps = defaults.clone();
+    for binding in &bindings {
+        keymaps
+            .entry(binding.mode)
+            .or_default()
+            .insert(binding.key.clone(), binding.action.clone());
+    }
+    for (i, binding) in bindings.iter().enumerate() {
+        // Only the binding read last is used by the app
+        let is_overridden = bindings[i + 1..]
+            .iter()
+            .any(|other| other.mode == binding.mode && other.key == binding.key);
+        if is_overridden {
+            continue;
+        }
+        let keymap = &keymaps[&binding.mode];
+        let key = &binding.key;
+        // The app runs the action of a key as soon as it is pressed
+        if let Some(len) = (1..key.len()).find(|len| keymap.contains_key(&key[..*len])) {
+            let prefix = key[..len]
+                .iter()
+                .map(|key| format!("<{}>", key_event_to_string(key)))
+                .collect::<String>();
+            report.add_in(
+                binding.file,
+                Severity::Warning,
+                &binding.location,
+                format!(
+                    "Never triggered, {} runs {} first",
+                    prefix,
+                    keymap[&key[..len]]
+                ),
+            );
+        }
+        let default_action = defaults
+            .get(&binding.mode)
+            .and_then(|keymap| keymap.get(key));
+        if let Some(default_action) = default_action {
+            if *default_action != binding.action && !keymap.values().any(|a| a == default_action) {
+                report.add_in(
+                    binding.file,
+                    Severity::Warning,
+                    &binding.location,
+                    format!(
+                        "Replaces {}, which is then bound to no key in {:?} mode",
+                        default_action, binding.mode
+                    ),
+                );
+            }
+        }
+    }
+}
+
+/// Whether `key` is a character typed as is, without ctrl or alt.
+fn is_typed(key: &[KeyEvent]) -> bool {
+    matches!(
+        key,
+        [KeyEvent { code: KeyCode::Char(_), modifiers, .. }]
+            if !modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+    )
+}
+
+fn check_contexts(report: &mut Report<'_>, config: &Config) {
+    if !config.airflow.host.is_empty() {
+        check_airflow(report, "airflow", &config.airflow);
+    }
+    let mut names: Vec<&str> = vec![];
+    for context in &config.contexts {
+        let location = format!("contexts.{}", context.name);
+        if names.contains(&context.name.as_str()) {
+            report.add(
+                Severity::Error,
+                &location,
+                "Another context has the same name",
+            );
+        }
+        names.push(&context.name);
+        check_airflow(report, &location, &context.airflow);
+    }
+    if let Some(default_context) = &config.default_context {
+        if !names.contains(&default_context.as_str()) {
+            report.add(
+                Severity::Error,
+                "default_context",
+                format!(
+                    "Unknown context `{}`, available contexts: {}",
+                    default_context,
+                    names.join(", ")
+                ),
+            );
+        }
+    }
+    if config.page_size == Some(0) {
+        report.add(
+            Severity::Warning,
+            "page_size",
+            "At least one dag run is fetched per request",
+        );
+    }
+}
+
+fn check_airflow(report: &mut Report<'_>, location: &str, airflow: &Airflow) {
+    match reqwest::Url::parse(&airflow.host) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+        _ => report.add(
+            Severity::Error,
+            &format!("{}.host", location),
+            format!(
+                "`{}` is not the URL of a webserver, e.g. https://airflow.example.com",
+                airflow.host
+            ),
+        ),
+    }
+    let tls = &airflow.tls;
+    if tls.client_cert.is_some() != tls.client_key.is_some() {
+        report.add(
+            Severity::Error,
+            &format!("{}.tls", location),
+            "client_cert and client_key must be set together",
+        );
+    }
+    for (name, path) in [
+        ("ca_file", &tls.ca_file),
+        ("client_cert", &tls.client_cert),
+        ("client_key", &tls.client_key),
+    ] {
+        if let Some(path) = path.as_ref().filter(|path| !path.is_file()) {
+            report.add(
+                Severity::Error,
+                &format!("{}.tls.{}", location, name),
+                format!("No file at {}", path.display()),
+            );
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use strum::IntoEnumIterator;
+
+use super::*;
+
+fn key(raw: &str) -> Vec<KeyEvent> {
+    parse_key_sequence(raw).unwrap()
+}
+
+#[test]
+fn defaults_bind_every_mode() {
+    let config = Config::defaults();
+    for mode in Mode::iter() {
+        let keybindings = config.keybindings.get(&mode);
+        assert_eq!(
+            keybindings.and_then(|keybindings| keybindings.get(&key("<ctrl-c>"))),
+            Some(&Action::Quit),
+            "{:?}",
+            mode
+        );
+    }
+    assert_eq!(
+        config.keybindings[&Mode::DagRun].get(&key("<enter>")),
+        Some(&Action::Task)
+    );
+}
+
+#[test]
+fn user_keybindings_are_merged_over_the_defaults() {
+    let mut config: Config = json5::from_str(
+        r#"{
+            "keybindings": {
+                "DagRun": { "<x>": "Clear", "<q>": "Refresh" },
+            },
+        }"#,
+    )
+    .unwrap();
+    config.merge_defaults(Config::defaults());
+
+    let dag_run = &config.keybindings[&Mode::DagRun];
+    assert_eq!(dag_run.get(&key("<x>")), Some(&Action::Clear));
+    assert_eq!(dag_run.get(&key("<q>")), Some(&Action::Refresh));
+    assert_eq!(dag_run.get(&key("<c>")), Some(&Action::Clear));
+    assert_eq!(
+        config.keybindings[&Mode::Task].get(&key("<enter>")),
+        Some(&Action::Log)
+    );
+}
+
+/// Diagnostics of a configuration directory holding `files`, as
+/// `severity file location: message` with the paths relative to the directory.
+fn check_dir(files: &[(&str, &str)]) -> Vec<String> {
+    let dir = tempfile::tempdir().unwrap();
+    for (name, content) in files {
+        std::fs::write(dir.path().join(name), content).unwrap();
+    }
+    let dir_prefix = format!("{}/", dir.path().display());
+    check::check_config_dir(dir.path())
+        .iter()
+        .map(|diagnostic| {
+            format!(
+                "{} {} {}: {}",
+                diagnostic.severity,
+                diagnostic.file.replace(&dir_prefix, ""),
+                diagnostic.location,
+                diagnostic.message.replace(&dir_prefix, "")
+            )
+        })
+        .collect()
+}
+
+/// Diagnostics of a configuration directory holding `json5` alone, as
+/// `severity config.json5 location: message`.
+fn check(json5: &str) -> Vec<String> {
+    check_dir(&[("config.json5", json5)])
+}
+
+#[test]
+fn invalid_keybindings_are_errors_instead_of_panics() {
+    let error =
+        json5::from_str::<Config>(r#"{ "keybindings": { "DagRun": { "<ctl-q>": "Quit" } } }"#)
+            .unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "keybindings.DagRun.<ctl-q>: Unable to parse ctl-q"
+    );
+}
+
+#[test]
+fn modes_and_actions_are_read_in_any_case() {
+    // The configuration files have their keys lowercased once read
+    let config: Config = json5::from_str(
+        r#"{ "keybindings": { "dagrun": { "<x>": "Quit", "<r>": { "runs": "my_dag" } } } }"#,
+    )
+    .unwrap();
+    let dag_run = &config.keybindings[&Mode::DagRun];
+    assert_eq!(dag_run.get(&key("<x>")), Some(&Action::Quit));
+    assert_eq!(
+        dag_run.get(&key("<r>")),
+        Some(&Action::Runs(Some(String::from("my_dag"))))
+    );
+}
+
+#[test]
+fn check_reports_every_invalid_keybinding() {
+    assert_eq!(
+        check(
+            r#"{
+                "airflow": { "host": "http://localhost:8080" },
+                "keybindings": {
+                    "DagRun": { "<ctl-q>": "Quit", "<x>": "Qiut", "<y>": "Frobnicate" },
+                    "Serch": { "<enter>": "DagRun" },
+                    "Task": "Quit",
+                },
+            }"#
+        ),
+        [
+            "error config.json5 keybindings.dagrun.<ctl-q>: Unable to parse ctl-q",
+            "error config.json5 keybindings.dagrun.<x>: Unknown action `Qiut`, did you mean \
+             `Quit`?",
+            "error config.json5 keybindings.dagrun.<y>: Unknown action `Frobnicate`",
+            "error config.json5 keybindings.serch: Unknown mode `serch`, did you mean `Search`?",
+            "error config.json5 keybindings.task: Expected the actions bound to each key",
+        ]
+    );
+}
+
+#[test]
+fn check_reports_conflicting_and_shadowed_keybindings() {
+    assert_eq!(
+        check(
+            r#"{
+                "airflow": { "host": "http://localhost:8080" },
+                "keybindings": {
+                    "DagRun": {
+                        "<alt-ctrl-x>": "Task",
+                        "<ctrl-alt-x>": "Log",
+                        "<q><q>": "Quit",
+                        "<c>": "Refresh",
+                    },
+                    "Search": { "<x>": "DagRun", "<ctrl-x>": "DagRun" },
+                },
+            }"#
+        ),
+        [
+            "error config.json5 keybindings.dagrun.<ctrl-alt-x>: Same key as `<alt-ctrl-x>`, \
+             bound to Task instead of Log",
+            "warning config.json5 keybindings.search.<x>: The key can no longer be typed in \
+             Search mode, prefer a key with ctrl or alt",
+            "warning config.json5 keybindings.dagrun.<c>: Replaces Clear, which is then bound to \
+             no key in DagRun mode",
+            "warning config.json5 keybindings.dagrun.<q><q>: Never triggered, <q> runs Quit \
+             first",
+        ]
+    );
+}
+
+#[test]
+fn check_merges_every_file() {
+    assert_eq!(
+        check_dir(&[
+            (
+                "config.json5",
+                r#"{
+                    "contexts": [{ "name": "prod", "host": "https://airflow.example.com" }],
+                    "keybindings": { "DagRun": { "<x>": "Clear", "<d>": "Refresh" } },
+                }"#,
+            ),
+            (
+                "config.toml",
+                r#"
+                    default_context = "prod"
+
+                    [keybindings.dagrun]
+                    "<x>" = "Quit"
+                    "<D>" = "Refresh"
+                    "<c><c>" = "Clear"
+                "#,
+            ),
+        ]),
+        [
+            "warning config.toml keybindings.dagrun.<d>: Same key and action as `<d>` in \
+             config.json5",
+            "error config.toml keybindings.dagrun.<x>: Same key as `<x>` in config.json5, bound \
+             to Clear instead of Quit",
+            "warning config.toml keybindings.dagrun.<c><c>: Never triggered, <c> runs Clear first",
+            "warning config.toml keybindings.dagrun.<d>: Replaces Code, which is then bound to no \
+             key in DagRun mode",
+        ]
+    );
+}
+
+#[test]
+fn check_reports_invalid_contexts() {
+    assert_eq!(
+        check(
+            r#"{
+                "default_context": "staging",
+                "contexts": [
+                    { "name": "prod", "host": "https://airflow.example.com" },
+                    { "name": "prod", "host": "airflow.example.com" },
+                    {
+                        "name": "dev",
+                        "host": "http://localhost:8080",
+                        "tls": { "client_cert": "/nonexistent/client.pem" },
+                    },
+                ],
+            }"#
+        ),
+        [
+            "error config.json5 contexts.prod: Another context has the same name",
+            "error config.json5 contexts.prod.host: `airflow.example.com` is not the URL of a \
+             webserver, e.g. https://airflow.example.com",
+            "error config.json5 contexts.dev.tls: client_cert and client_key must be set together",
+            "error config.json5 contexts.dev.tls.client_cert: No file at /nonexistent/client.pem",
+            "error config.json5 default_context: Unknown context `staging`, available contexts: \
+             prod, prod, dev",
+        ]
+    );
+}
+
+#[test]
+fn check_accepts_the_defaults() {
+    assert_eq!(
+        check_dir(&[
+            ("config.json5", CONFIG),
+            (
+                "config.toml",
+                "[airflow]\nhost = \"http://localhost:8080\"\n"
+            ),
+        ]),
+        Vec::<String>::new()
+    );
+}
+
+fn resolve(secret: &str) -> Result<String, String> {
+    let mut secret: Secret = json5::from_str(secret).unwrap();
+    secret.resolve()?;
+    Ok(secret.expose().to_string())
+}
+
+#[test]
+fn resolve_secrets() {
+    assert_eq!(resolve(r#""admin""#), Ok(String::from("admin")));
+
+    std::env::set_var("ARVZ_TEST_SECRET", "from env");
+    assert_eq!(
+        resolve(r#"{ env: "ARVZ_TEST_SECRET" }"#),
+        Ok(String::from("from env"))
+    );
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(file.path(), "from file\r\n").unwrap();
+    assert_eq!(
+        resolve(&format!(r#"{{ file: "{}" }}"#, file.path().display())),
+        Ok(String::from("from file"))
+    );
+
+    assert_eq!(
+        resolve(r#"{ command: "echo 'from command'" }"#),
+        Ok(String::from("from command"))
+    );
+}
+
+#[test]
+fn resolve_unavailable_secrets() {
+    assert_eq!(
+        resolve(r#"{ env: "ARVZ_TEST_UNSET_SECRET" }"#),
+        Err(String::from(
+            "environment variable `ARVZ_TEST_UNSET_SECRET` is not set"
+        ))
+    );
+    assert!(resolve(r#"{ file: "/nonexistent/password" }"#)
+        .unwrap_err()
+        .starts_with("cannot read /nonexistent/password: "));
+    assert_eq!(
+        resolve(r#"{ command: "echo denied >&2; exit 3" }"#),
+        Err(String::from("`echo denied >&2; exit 3` failed: denied"))
+    );
+}
@@ -0,0 +1,44 @@
+use std::fs::File;
+use std::path::Path;
+
+use color_eyre::eyre::{eyre, Result};
+use serde::Serialize;
+
+/// Format of an exported table, guessed from the extension of the file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("csv") => Ok(ExportFormat::Csv),
+            Some("json") => Ok(ExportFormat::Json),
+            _ => Err(eyre!("{} must end with .csv or .json", path.display())),
+        }
+    }
+}
+
+/// Write `records` to `path`, as CSV with a header line or as a JSON array.
+pub fn export_records<T: Serialize>(records: &[T], path: &Path) -> Result<()> {
+    let format = ExportFormat::from_path(path)?;
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    match format {
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_path(path)?;
+            for record in records {
+                writer.serialize(record)?;
+            }
+            writer.flush()?;
+        }
+        ExportFormat::Json => serde_json::to_writer_pretty(File::create(path)?, records)?,
+    }
+    Ok(())
+}
@@ -5,14 +5,20 @@
 pub mod action;
 pub mod app;
 pub mod cli;
+pub mod client;
+pub mod commands;
 pub mod components;
 pub mod config;
+pub mod export;
 pub mod main_layout;
 pub mod mode;
 pub mod models;
 pub mod style;
+#[cfg(test)]
+mod test_utils;
 pub mod tui;
 pub mod utils;
+pub mod worker;
 
 use clap::Parser;
 use cli::Cli;
@@ -31,7 +37,10 @@ async fn tokio_main() -> Result<()> {
     initialize_panic_handler()?;
 
     let args = Cli::parse();
-    let mut app = App::new(args.tick_rate, args.frame_rate).await?;
+    if let Some(command) = args.command {
+        return commands::run(command, args.output, args.context).await;
+    }
+    let mut app = App::new(args.tick_rate, args.frame_rate, args.context).await?;
     app.run().await?;
     Ok(())
 }
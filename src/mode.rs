@@ -4,7 +4,9 @@ use std::cell::RefCell;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(
+    Default, Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, strum::EnumIter,
+)]
 pub enum Mode {
     #[default]
     DagRun,
@@ -13,6 +15,12 @@ pub enum Mode {
     Log,
     Code,
     Command,
+    Dag,
+    Pool,
+    Confirm,
+    Clear,
+    Mark,
+    TextSearch,
 }
 
 pub type RefreshLayoutFnType = Option<RefCell<Box<dyn FnMut(Mode)>>>;
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// Options of a clear, toggled by the user before applying it.
+#[derive(Serialize, Debug, Default, Clone, Copy, PartialEq)]
+pub struct ClearOptions {
+    pub only_failed: bool,
+    pub include_upstream: bool,
+    pub include_downstream: bool,
+    pub include_future: bool,
+    pub include_past: bool,
+}
+
+/// Clear of the task instances of a dag run, or of some of its tasks only.
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct ClearRequest {
+    #[serde(skip)]
+    pub dag_id: String,
+    pub dag_run_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_ids: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub options: ClearOptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct TaskInstanceReference {
+    pub(crate) dag_id: String,
+    pub(crate) dag_run_id: String,
+    pub(crate) task_id: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub(crate) struct TaskInstanceReferences {
+    pub(crate) task_instances: Vec<TaskInstanceReference>,
+}
+
+impl ClearRequest {
+    /// Clear every task instance of a dag run.
+    pub fn dag_run(dag_id: &str, dag_run_id: &str) -> Self {
+        Self {
+            dag_id: dag_id.to_string(),
+            dag_run_id: dag_run_id.to_string(),
+            task_ids: None,
+            options: ClearOptions::default(),
+        }
+    }
+
+    /// Clear a single task instance of a dag run.
+    pub fn task(dag_id: &str, dag_run_id: &str, task_id: &str) -> Self {
+        Self {
+            task_ids: Some(vec![task_id.to_string()]),
+            ..Self::dag_run(dag_id, dag_run_id)
+        }
+    }
+}
@@ -1,4 +1,12 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 
-#[derive(Deserialize, Debug, Default, Clone)]
-pub struct Conf {}
+/// Configuration of a dag run, any JSON object is accepted.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Conf(pub Value);
+
+impl Default for Conf {
+    fn default() -> Self {
+        Self(Value::Object(Map::new()))
+    }
+}
@@ -1,63 +1,81 @@
+use crate::models::de::null_as_default;
 use serde::Deserialize;
+use serde_json::Value;
 
-#[derive(Debug, Default, Deserialize)]
+/// A dag, as returned by both `/dags` and `/dags/{dag_id}/details`.
+/// The fields only returned by the details endpoint default when missing.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
 pub struct Dag {
     pub(crate) dag_id: String,
     default_view: Option<String>,
     description: Option<String>,
+    #[serde(deserialize_with = "null_as_default")]
     pub(crate) file_token: String,
+    #[serde(deserialize_with = "null_as_default")]
     fileloc: String,
-    has_import_errors: Option<bool>,
+    pub(crate) has_import_errors: Option<bool>,
     has_task_concurrency_limits: Option<bool>,
     is_active: Option<bool>,
-    is_paused: Option<bool>,
+    pub(crate) is_paused: Option<bool>,
+    #[serde(deserialize_with = "null_as_default")]
     is_subdag: bool,
     last_expired: Option<String>,
     last_parsed_time: Option<String>,
     last_pickled: Option<String>,
-    max_active_runs: Option<i32>,
-    max_active_tasks: Option<i32>,
-    next_dagrun: Option<String>,
+    max_active_runs: Option<i64>,
+    max_active_tasks: Option<i64>,
+    pub(crate) next_dagrun: Option<String>,
     next_dagrun_create_after: Option<String>,
     next_dagrun_data_interval_end: Option<String>,
     next_dagrun_data_interval_start: Option<String>,
-    owners: Vec<String>,
+    #[serde(deserialize_with = "null_as_default")]
+    pub(crate) owners: Vec<String>,
     pickle_id: Option<String>,
     root_dag_id: Option<String>,
     schedule_interval: Option<ScheduleInterval>,
     scheduler_lock: Option<bool>,
-    tags: Option<Vec<Tag>>,
-    timetable_description: Option<String>,
+    pub(crate) tags: Option<Vec<Tag>>,
+    pub(crate) timetable_description: Option<String>,
+    #[serde(deserialize_with = "null_as_default")]
     catchup: bool,
-    concurrency: i32,
+    #[serde(deserialize_with = "null_as_default")]
+    concurrency: i64,
     dag_run_timeout: Option<DagRunTimeout>,
     doc_md: Option<String>,
     end_date: Option<String>,
     is_paused_upon_creation: Option<bool>,
     last_parsed: Option<String>,
+    #[serde(deserialize_with = "null_as_default")]
     orientation: String,
-    params: Params,
+    params: Value,
     render_template_as_native_obj: Option<bool>,
     start_date: Option<String>,
     template_search_path: Option<Vec<String>>,
+    #[serde(deserialize_with = "null_as_default")]
     timezone: String,
 }
 
-#[derive(Debug, Default, Deserialize)]
+impl Dag {
+    pub fn is_paused(&self) -> bool {
+        self.is_paused.unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
 pub struct ScheduleInterval {}
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
 pub struct Tag {
-    name: String,
+    pub(crate) name: String,
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
 pub struct DagRunTimeout {
     __type: String,
-    days: i32,
-    microseconds: i32,
-    seconds: i32,
+    days: i64,
+    microseconds: i64,
+    seconds: i64,
 }
-
-#[derive(Debug, Default, Deserialize)]
-pub struct Params {}
@@ -1,74 +1,108 @@
-use crate::config::Airflow;
+use crate::models::clear::ClearRequest;
 use crate::models::conf::Conf;
-use crate::models::tasks::Tasks;
-use color_eyre::eyre::Result;
-use reqwest::{Client, StatusCode};
-use serde::Deserialize;
+use crate::models::de::null_as_default;
+use crate::models::mark::{MarkRequest, MarkState};
+use color_eyre::eyre::{eyre, Result};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use super::dag::Dag;
-
-#[derive(Deserialize, Debug, Default, Clone)]
+/// A dag run. The data interval and the logical date are only known since Airflow 2.2, the
+/// execution date stands for both before.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
 pub struct DagRun {
+    #[serde(deserialize_with = "null_as_default")]
     conf: Conf,
     pub(crate) dag_id: String,
     pub(crate) dag_run_id: String,
-    pub(crate) data_interval_end: String,
-    pub(crate) data_interval_start: String,
-    end_date: Option<String>,
+    pub(crate) data_interval_end: Option<String>,
+    pub(crate) data_interval_start: Option<String>,
+    pub(crate) end_date: Option<String>,
+    execution_date: Option<String>,
+    #[serde(deserialize_with = "null_as_default")]
     pub(crate) external_trigger: bool,
     last_scheduling_decision: Option<String>,
-    logical_date: String,
+    logical_date: Option<String>,
     note: Option<String>,
+    #[serde(deserialize_with = "null_as_default")]
     pub(crate) run_type: String,
-    start_date: Option<String>,
+    pub(crate) start_date: Option<String>,
+    #[serde(deserialize_with = "null_as_default")]
     pub(crate) state: String,
 }
 
+/// Parameters of a new dag run to trigger.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct DagRunTrigger {
+    #[serde(default)]
+    pub conf: Conf,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logical_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+impl DagRunTrigger {
+    /// Template edited by the user to trigger a new run of `dag_id`.
+    pub fn template(dag_id: &str) -> String {
+        format!(
+            "\
+// A new run of `{dag_id}` is triggered once saved with a change, e.g. without this line.
+// Quit without saving or delete everything to cancel.
+{{
+  // Configuration passed to the dag run, any JSON object
+  conf: {{}},
+  // Optional logical date, e.g. \"2024-01-31T00:00:00Z\"
+  logical_date: null,
+  // Optional note displayed on the dag run
+  note: null,
+}}
+"
+        )
+    }
+
+    /// Parse the template edited by the user, `None` if everything was deleted.
+    pub fn parse(content: &str) -> Result<Option<Self>> {
+        if content.trim().is_empty() {
+            return Ok(None);
+        }
+        let trigger: Self = json5::from_str(content)?;
+        if !trigger.conf.0.is_object() {
+            return Err(eyre!("conf must be a JSON object"));
+        }
+        Ok(Some(trigger))
+    }
+}
+
 impl DagRun {
     pub fn new() -> Self {
         Self::default()
     }
 
-    pub async fn clear(
-        &mut self,
-        client: &Client,
-        cfg_airflow: &Airflow,
-        username: &str,
-        password: &str,
-        url: &str,
-    ) -> Result<()> {
-        let mut map = HashMap::new();
-        map.insert("dry_run", false);
-        let task = client
-            .post(format!(
-                "{}/api/v1/dags/{}/dagRuns/{}/clear",
-                &cfg_airflow.host, self.dag_id, self.dag_run_id
-            ))
-            .basic_auth(&cfg_airflow.username, Some(&cfg_airflow.password))
-            .json(&map)
-            .send()
-            .await?;
-        Ok(())
+    /// Start of the data interval, the logical date before Airflow 2.2.
+    pub fn get_interval_start(&self) -> Option<&str> {
+        self.data_interval_start
+            .as_deref()
+            .or(self.logical_date.as_deref())
+            .or(self.execution_date.as_deref())
     }
 
-    pub async fn get_source_code(&mut self, client: &Client, cfg: &Airflow) -> Result<String> {
-        let res = client
-            .get(format!("{}/api/v1/dags/{}/details", cfg.host, self.dag_id))
-            .basic_auth(&cfg.username, Some(&cfg.password))
-            .send()
-            .await?;
-        if res.status() == StatusCode::OK {
-            let dag = res.json::<Dag>().await?;
-            let source_code = client
-                .get(format!("{}/api/v1/dagSources/{}", cfg.host, dag.file_token))
-                .basic_auth(&cfg.username, Some(&cfg.password))
-                .send()
-                .await?
-                .text()
-                .await?;
-            return Ok(source_code);
-        }
-        Ok(String::from(""))
+    /// End of the data interval, the logical date before Airflow 2.2.
+    pub fn get_interval_end(&self) -> Option<&str> {
+        self.data_interval_end
+            .as_deref()
+            .or(self.logical_date.as_deref())
+            .or(self.execution_date.as_deref())
+    }
+
+    pub fn clear_request(&self) -> ClearRequest {
+        ClearRequest::dag_run(&self.dag_id, &self.dag_run_id)
+    }
+
+    pub fn mark_request(&self, state: MarkState) -> MarkRequest {
+        MarkRequest::dag_run(&self.dag_id, &self.dag_run_id, state)
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,52 @@
+use serde_json::json;
+
+use super::*;
+
+#[test]
+fn parse_the_template() {
+    let trigger = DagRunTrigger::parse(&DagRunTrigger::template("etl")).unwrap();
+    assert_eq!(
+        trigger,
+        Some(DagRunTrigger {
+            conf: Conf(json!({})),
+            logical_date: None,
+            note: None,
+        })
+    );
+}
+
+#[test]
+fn parse_an_edited_trigger() {
+    let trigger = DagRunTrigger::parse(
+        r#"{
+            conf: { tables: ["users"], full: true },
+            logical_date: "2024-01-31T00:00:00Z",
+            note: "backfill",
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(
+        trigger,
+        Some(DagRunTrigger {
+            conf: Conf(json!({ "tables": ["users"], "full": true })),
+            logical_date: Some(String::from("2024-01-31T00:00:00Z")),
+            note: Some(String::from("backfill")),
+        })
+    );
+}
+
+#[test]
+fn parse_an_emptied_trigger() {
+    assert_eq!(DagRunTrigger::parse(" \n").unwrap(), None);
+}
+
+#[test]
+fn parse_an_invalid_trigger() {
+    assert_eq!(
+        DagRunTrigger::parse("{ conf: [1, 2] }")
+            .unwrap_err()
+            .to_string(),
+        "conf must be a JSON object"
+    );
+    assert!(DagRunTrigger::parse("{ conf: {").is_err());
+}
@@ -1,17 +1,19 @@
-use crate::config::Airflow;
 use crate::models::dag_run::DagRun;
+use crate::models::dags::Dags;
+use crate::models::de::skip_invalid;
 use crate::models::tasks::Tasks;
 use crate::style;
 use color_eyre::eyre::Result;
 use color_eyre::owo_colors::OwoColorize;
 use ratatui::style::{Style, Stylize};
 use ratatui::widgets::Row;
-use reqwest::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug, Default, Clone)]
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct DagRuns {
+    #[serde(deserialize_with = "skip_invalid")]
     pub(crate) dag_runs: Vec<DagRun>,
+    #[serde(default)]
     total_entries: u32,
 }
 
@@ -23,49 +25,49 @@ impl DagRuns {
         }
     }
 
-    pub async fn set_dag_runs(
-        &mut self,
-        client: &Client,
-        username: &str,
-        password: &str,
-        url: &str,
-    ) -> Result<()> {
-        let dag_runs: DagRuns = client
-            .get(format!(
-                "{}/api/v1/dags/~/dagRuns?order_by=-start_date",
-                url
-            ))
-            .basic_auth(username, Some(password))
-            .send()
-            .await?
-            .json::<DagRuns>()
-            .await?;
-
-        self.dag_runs = dag_runs.dag_runs;
-        self.total_entries = dag_runs.total_entries;
-        Ok(())
-    }
-
-    pub async fn get_task(
-        &mut self,
-        client: &Client,
-        cfg_airflow: &Airflow,
-        username: &str,
-        password: &str,
-        url: &str,
-        index: usize,
-    ) -> Result<Tasks> {
-        let task: Tasks = client
-            .get(format!(
-                "{}/api/v1/dags/{}/dagRuns/{}/taskInstances",
-                &cfg_airflow.host, self.dag_runs[index].dag_id, self.dag_runs[index].dag_run_id
-            ))
-            .basic_auth(&cfg_airflow.username, Some(&cfg_airflow.password))
-            .send()
-            .await?
-            .json::<Tasks>()
-            .await?;
-        Ok(task)
+    /// Replace the most recent dag runs by a freshly fetched first `page`,
+    /// the older pages already loaded are kept.
+    pub fn merge_first_page(&mut self, page: DagRuns) {
+        let older_dag_runs = std::mem::replace(&mut self.dag_runs, page.dag_runs);
+        for dag_run in older_dag_runs {
+            if !self.contains(&dag_run) {
+                self.dag_runs.push(dag_run);
+            }
+        }
+        self.total_entries = page.total_entries;
+    }
+
+    /// Append the next `page` of older dag runs.
+    /// New runs shift the offsets, so runs already loaded are skipped.
+    pub fn append_page(&mut self, page: DagRuns) {
+        for dag_run in page.dag_runs {
+            if !self.contains(&dag_run) {
+                self.dag_runs.push(dag_run);
+            }
+        }
+        self.total_entries = page.total_entries;
+    }
+
+    /// Insert a dag run that was just triggered at the top of the list.
+    pub fn insert_dag_run(&mut self, dag_run: DagRun) {
+        if !self.contains(&dag_run) {
+            self.dag_runs.insert(0, dag_run);
+            self.total_entries += 1;
+        }
+    }
+
+    fn contains(&self, dag_run: &DagRun) -> bool {
+        self.dag_runs
+            .iter()
+            .any(|d| d.dag_id == dag_run.dag_id && d.dag_run_id == dag_run.dag_run_id)
+    }
+
+    pub fn has_more(&self) -> bool {
+        self.get_loaded_entries() < self.total_entries
+    }
+
+    pub fn get_loaded_entries(&self) -> u32 {
+        self.dag_runs.len() as u32
     }
 
     pub fn get_total_entries(&self) -> u32 {
@@ -114,42 +116,84 @@ impl DagRuns {
             .collect::<Vec<&'a DagRun>>()
     }
 
-    pub fn get_dag_runs_rows_filtered(&self, dag_id: &str) -> Vec<Row> {
-        let mut rows: Vec<Row> = Vec::new();
-        let filtered_dag_runs: Vec<&DagRun> = self.filter_runs_by_dag_id(dag_id);
-
-        for dag_run in filtered_dag_runs {
-            rows.push(
-                Row::new(vec![
-                    dag_run.dag_id.clone(),
-                    dag_run.state.clone(),
-                    dag_run.data_interval_start.clone(),
-                    dag_run.data_interval_end.clone(),
-                    dag_run.run_type.clone(),
-                    dag_run.external_trigger.to_string().clone(),
-                ])
-                .style(style::get_style_row(&dag_run.state)),
-            );
-        }
-        rows
-    }
-
-    pub fn get_dag_runs_rows_context(&self) -> Vec<Row> {
-        let mut rows: Vec<Row> = Vec::new();
-
-        for dag_run in &self.dag_runs {
-            rows.push(
-                Row::new(vec![
-                    dag_run.dag_id.clone(),
-                    dag_run.state.clone(),
-                    dag_run.data_interval_start.clone(),
-                    dag_run.data_interval_end.clone(),
-                    dag_run.run_type.clone(),
-                    dag_run.external_trigger.to_string().clone(),
-                ])
-                .style(style::get_style_row(&dag_run.state)),
-            );
+    /// The dag runs displayed in the table, filtered on `search` if any.
+    pub fn get_records(&self, search: Option<&str>) -> Vec<DagRunRecord> {
+        let dag_runs: Vec<&DagRun> = match search {
+            Some(dag_id) => self.filter_runs_by_dag_id(dag_id),
+            None => self.dag_runs.iter().collect(),
+        };
+        dag_runs.into_iter().map(DagRunRecord::from).collect()
+    }
+
+    pub fn get_dag_runs_rows_filtered(&self, dag_id: &str, dags: &Dags) -> Vec<Row<'_>> {
+        get_dag_runs_rows(self.get_records(Some(dag_id)), dags)
+    }
+
+    pub fn get_dag_runs_rows_context(&self, dags: &Dags) -> Vec<Row<'_>> {
+        get_dag_runs_rows(self.get_records(None), dags)
+    }
+}
+
+/// A dag run as displayed in the table, independently of how it is rendered.
+/// The table shows its data interval, the exports also give when it actually ran.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DagRunRecord {
+    pub dag_id: String,
+    pub dag_run_id: String,
+    pub state: String,
+    pub data_interval_start: Option<String>,
+    pub data_interval_end: Option<String>,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub run_type: String,
+    pub external_trigger: bool,
+}
+
+impl From<&DagRun> for DagRunRecord {
+    fn from(dag_run: &DagRun) -> Self {
+        Self {
+            dag_id: dag_run.dag_id.clone(),
+            dag_run_id: dag_run.dag_run_id.clone(),
+            state: dag_run.state.clone(),
+            data_interval_start: dag_run.get_interval_start().map(String::from),
+            data_interval_end: dag_run.get_interval_end().map(String::from),
+            start_date: dag_run.start_date.clone(),
+            end_date: dag_run.end_date.clone(),
+            run_type: dag_run.run_type.clone(),
+            external_trigger: dag_run.external_trigger,
         }
-        rows
     }
 }
+
+fn get_dag_runs_rows(records: Vec<DagRunRecord>, dags: &Dags) -> Vec<Row<'static>> {
+    records
+        .into_iter()
+        .map(|record| {
+            Row::new(vec![
+                get_dag_id_cell(&record.dag_id, dags),
+                record.state.clone(),
+                record
+                    .data_interval_start
+                    .unwrap_or_else(|| String::from("n/a")),
+                record
+                    .data_interval_end
+                    .unwrap_or_else(|| String::from("n/a")),
+                record.run_type,
+                record.external_trigger.to_string(),
+            ])
+            .style(style::get_style_row(&record.state))
+        })
+        .collect()
+}
+
+// Paused dags are marked in front of their id
+fn get_dag_id_cell(dag_id: &str, dags: &Dags) -> String {
+    if dags.is_paused(dag_id) {
+        format!("⏸ {}", dag_id)
+    } else {
+        dag_id.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests;
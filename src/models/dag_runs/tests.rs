@@ -0,0 +1,97 @@
+use super::*;
+
+fn dag_run(dag_run_id: &str) -> DagRun {
+    let mut dag_run = DagRun::new();
+    dag_run.dag_id = String::from("etl");
+    dag_run.dag_run_id = String::from(dag_run_id);
+    dag_run
+}
+
+fn page(dag_run_ids: &[&str], total_entries: u32) -> DagRuns {
+    DagRuns {
+        dag_runs: dag_run_ids.iter().map(|id| dag_run(id)).collect(),
+        total_entries,
+    }
+}
+
+fn ids(dag_runs: &DagRuns) -> Vec<&str> {
+    dag_runs
+        .dag_runs
+        .iter()
+        .map(|dag_run| dag_run.dag_run_id.as_str())
+        .collect()
+}
+
+#[test]
+fn merge_first_page_keeps_the_older_pages() {
+    let mut dag_runs = page(&["run_3", "run_2", "run_1", "run_0"], 4);
+    dag_runs.merge_first_page(page(&["run_4", "run_3"], 5));
+    assert_eq!(
+        ids(&dag_runs),
+        ["run_4", "run_3", "run_2", "run_1", "run_0"]
+    );
+    assert_eq!(dag_runs.get_total_entries(), 5);
+}
+
+#[test]
+fn merge_first_page_refreshes_the_loaded_runs() {
+    let mut dag_runs = page(&["run_1", "run_0"], 2);
+    let mut refreshed = page(&["run_1"], 2);
+    refreshed.dag_runs[0].state = String::from("success");
+    dag_runs.merge_first_page(refreshed);
+    assert_eq!(ids(&dag_runs), ["run_1", "run_0"]);
+    assert_eq!(dag_runs.dag_runs[0].state, "success");
+}
+
+#[test]
+fn append_page_skips_the_runs_already_loaded() {
+    // A new run shifted the offsets, the first run of the next page was already loaded
+    let mut dag_runs = page(&["run_4", "run_3"], 5);
+    assert!(dag_runs.has_more());
+    dag_runs.append_page(page(&["run_3", "run_2"], 6));
+    assert_eq!(ids(&dag_runs), ["run_4", "run_3", "run_2"]);
+    assert_eq!(dag_runs.get_total_entries(), 6);
+    assert!(dag_runs.has_more());
+}
+
+#[test]
+fn append_the_last_page() {
+    let mut dag_runs = page(&["run_1"], 2);
+    dag_runs.append_page(page(&["run_0"], 2));
+    assert_eq!(ids(&dag_runs), ["run_1", "run_0"]);
+    assert!(!dag_runs.has_more());
+}
+
+#[test]
+fn records_give_the_data_interval_and_when_the_run_ran() {
+    let dag_runs: DagRuns = serde_json::from_str(
+        r#"{
+            "dag_runs": [{
+                "dag_id": "etl",
+                "dag_run_id": "scheduled__2024-03-11T00:00:00+00:00",
+                "data_interval_start": "2024-03-11T00:00:00+00:00",
+                "data_interval_end": "2024-03-12T00:00:00+00:00",
+                "start_date": "2024-03-12T00:00:01+00:00",
+                "end_date": null,
+                "run_type": "scheduled",
+                "state": "running"
+            }],
+            "total_entries": 1
+        }"#,
+    )
+    .unwrap();
+    let record = &dag_runs.get_records(None)[0];
+    assert_eq!(
+        record.data_interval_start.as_deref(),
+        Some("2024-03-11T00:00:00+00:00")
+    );
+    assert_eq!(
+        record.data_interval_end.as_deref(),
+        Some("2024-03-12T00:00:00+00:00")
+    );
+    assert_eq!(
+        record.start_date.as_deref(),
+        Some("2024-03-12T00:00:01+00:00")
+    );
+    assert_eq!(record.end_date, None);
+}
@@ -0,0 +1,75 @@
+use crate::models::dag::Dag;
+use crate::models::de::skip_invalid;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::Row;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Dags {
+    #[serde(deserialize_with = "skip_invalid")]
+    pub(crate) dags: Vec<Dag>,
+    #[serde(default)]
+    pub(crate) total_entries: u32,
+}
+
+impl Dags {
+    pub fn new() -> Self {
+        Self {
+            dags: vec![],
+            total_entries: 0,
+        }
+    }
+
+    pub fn get_dag(&self, dag_id: &str) -> Option<&Dag> {
+        self.dags.iter().find(|dag| dag.dag_id == dag_id)
+    }
+
+    pub fn is_paused(&self, dag_id: &str) -> bool {
+        self.get_dag(dag_id).is_some_and(Dag::is_paused)
+    }
+
+    /// Replace a dag by its updated version.
+    pub fn update_dag(&mut self, dag: Dag) {
+        if let Some(existing) = self.dags.iter_mut().find(|d| d.dag_id == dag.dag_id) {
+            *existing = dag;
+        }
+    }
+
+    pub fn get_total_entries(&self) -> u32 {
+        self.total_entries
+    }
+
+    pub fn get_dags_row(&self) -> Vec<Row<'_>> {
+        let mut rows: Vec<Row> = Vec::new();
+
+        for dag in &self.dags {
+            let has_import_errors = dag.has_import_errors.unwrap_or(false);
+            rows.push(
+                Row::new(vec![
+                    dag.dag_id.clone(),
+                    dag.owners.join(", "),
+                    dag.timetable_description
+                        .clone()
+                        .unwrap_or("n/a".to_string()),
+                    dag.tags
+                        .iter()
+                        .flatten()
+                        .map(|tag| tag.name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    dag.next_dagrun.clone().unwrap_or("n/a".to_string()),
+                    dag.is_paused().to_string(),
+                    has_import_errors.to_string(),
+                ])
+                .style(if has_import_errors {
+                    Style::default().fg(Color::Red)
+                } else if dag.is_paused() {
+                    Style::default().fg(Color::DarkGray)
+                } else {
+                    Style::default().fg(Color::White)
+                }),
+            );
+        }
+        rows
+    }
+}
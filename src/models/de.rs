@@ -0,0 +1,36 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// Deserialize a list whose items that cannot be decoded are skipped with a warning, so that a
+/// single item of an unexpected shape does not fail the whole list.
+pub(crate) fn skip_invalid<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: DeserializeOwned,
+{
+    let values = Vec::<Value>::deserialize(deserializer)?;
+    Ok(values
+        .into_iter()
+        .filter_map(|value| match T::deserialize(value) {
+            Ok(item) => Some(item),
+            Err(e) => {
+                log::warn!(
+                    "Skipped a {} of an unexpected shape: {}",
+                    std::any::type_name::<T>(),
+                    e
+                );
+                None
+            }
+        })
+        .collect())
+}
+
+/// Deserialize `null` as the default value, for the fields left null by some Airflow versions.
+pub(crate) fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
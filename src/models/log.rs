@@ -1,7 +1,114 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use serde::Deserialize;
 
+/// A chunk of the log of a task instance.
+/// `continuation_token` is given back to Airflow to fetch the content written after this chunk.
 #[derive(Debug, Default, Deserialize)]
 pub struct Log {
-    content: String,
-    continuation_token: String,
+    pub(crate) content: String,
+    pub(crate) continuation_token: Option<String>,
 }
+
+/// Metadata of the log read so far, signed by the webserver into the continuation token.
+#[derive(Debug, Default, Deserialize)]
+struct LogMetadata {
+    #[serde(default)]
+    end_of_log: bool,
+}
+
+impl Log {
+    /// The text of the chunk. Airflow 2 gives the Python representation of a list of
+    /// `(host, text)` tuples, the texts are read from it. Any other content is plain text.
+    pub fn get_text(&self) -> String {
+        parse_host_logs(&self.content)
+            .map(|logs| logs.concat())
+            .unwrap_or_else(|| self.content.clone())
+    }
+
+    /// Whether the task instance will not write to the log anymore, read from the metadata of
+    /// the continuation token.
+    pub fn is_end_of_log(&self) -> bool {
+        self.continuation_token
+            .as_deref()
+            .and_then(read_metadata)
+            .is_some_and(|metadata| metadata.end_of_log)
+    }
+}
+
+/// The metadata of `token`, signed as `payload.signature` with the base64 encoded JSON
+/// payload. A compressed payload, starting with a `.`, is not read.
+fn read_metadata(token: &str) -> Option<LogMetadata> {
+    let (payload, _signature) = token.rsplit_once('.')?;
+    let payload = URL_SAFE_NO_PAD.decode(payload).ok()?;
+    serde_json::from_slice(&payload).ok()
+}
+
+/// The texts of `content` formatted as `[('host', 'text'), ...]`, `None` if it is not.
+fn parse_host_logs(content: &str) -> Option<Vec<String>> {
+    let mut chars = content.trim().chars().peekable();
+    let mut logs = vec![];
+    if chars.next()? != '[' {
+        return None;
+    }
+    loop {
+        skip_spaces(&mut chars);
+        match chars.next()? {
+            ']' => break,
+            '(' => {}
+            _ => return None,
+        }
+        skip_spaces(&mut chars);
+        parse_python_str(&mut chars)?;
+        skip_spaces(&mut chars);
+        if chars.next()? != ',' {
+            return None;
+        }
+        skip_spaces(&mut chars);
+        logs.push(parse_python_str(&mut chars)?);
+        skip_spaces(&mut chars);
+        if chars.next()? != ')' {
+            return None;
+        }
+        skip_spaces(&mut chars);
+        match chars.next()? {
+            ']' => break,
+            ',' => {}
+            _ => return None,
+        }
+    }
+    chars.next().is_none().then_some(logs)
+}
+
+fn skip_spaces(chars: &mut std::iter::Peekable<impl Iterator<Item = char>>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+/// A Python string literal quoted by `'` or `"`, as given by `repr`.
+fn parse_python_str(chars: &mut impl Iterator<Item = char>) -> Option<String> {
+    let quote = chars.next().filter(|c| matches!(c, '\'' | '"'))?;
+    let mut value = String::new();
+    loop {
+        match chars.next()? {
+            c if c == quote => return Some(value),
+            '\\' => match chars.next()? {
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                'x' => value.push(parse_code_point(chars, 2)?),
+                'u' => value.push(parse_code_point(chars, 4)?),
+                'U' => value.push(parse_code_point(chars, 8)?),
+                c => value.push(c),
+            },
+            c => value.push(c),
+        }
+    }
+}
+
+fn parse_code_point(chars: &mut impl Iterator<Item = char>, digits: usize) -> Option<char> {
+    let hex: String = chars.take(digits).collect();
+    char::from_u32(u32::from_str_radix(&hex, 16).ok()?)
+}
+
+#[cfg(test)]
+mod tests;
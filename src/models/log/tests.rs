@@ -0,0 +1,42 @@
+use super::*;
+
+fn log(content: &str, continuation_token: Option<&str>) -> Log {
+    Log {
+        content: String::from(content),
+        continuation_token: continuation_token.map(String::from),
+    }
+}
+
+#[test]
+fn text_of_host_logs() {
+    let content = r#"[('worker-0', '*** Found local files:\n[2024-03-12] INFO - it\'s "done"\n'), ("worker-1", "it's \\ \x1b é")]"#;
+    assert_eq!(
+        log(content, None).get_text(),
+        "*** Found local files:\n[2024-03-12] INFO - it's \"done\"\nit's \\ \u{1b} é"
+    );
+    assert_eq!(log("[]", None).get_text(), "");
+}
+
+#[test]
+fn text_of_plain_logs() {
+    for content in [
+        "[2024-03-12, 00:01:03 UTC] {taskinstance.py:2193} INFO - Starting attempt 2 of 2\n",
+        "[('worker-0', 'unterminated)]",
+        "[('worker-0', 'text')] trailing",
+        "",
+    ] {
+        assert_eq!(log(content, None).get_text(), content);
+    }
+}
+
+#[test]
+fn end_of_log() {
+    // {"end_of_log":true,"log_pos":1944}
+    let ended = "eyJlbmRfb2ZfbG9nIjp0cnVlLCJsb2dfcG9zIjoxOTQ0fQ.Q2cP3xVAbhVnX0m0Hq3XIyr6a0k";
+    // {"end_of_log":false,"log_pos":1843}
+    let running = "eyJlbmRfb2ZfbG9nIjpmYWxzZSwibG9nX3BvcyI6MTg0M30.xfAzrZkQ1sW_xnYlKzNpR7iu3U0";
+    assert!(log("", Some(ended)).is_end_of_log());
+    assert!(!log("", Some(running)).is_end_of_log());
+    assert!(!log("", Some("not a token")).is_end_of_log());
+    assert!(!log("", None).is_end_of_log());
+}
@@ -0,0 +1,62 @@
+use std::str::FromStr;
+
+use strum::Display;
+
+/// Severity of a log line, ordered from the least to the most severe.
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[strum(serialize_all = "UPPERCASE")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warning,
+    Error,
+    Critical,
+}
+
+impl FromStr for LogLevel {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "DEBUG" => Ok(LogLevel::Debug),
+            "INFO" => Ok(LogLevel::Info),
+            "WARNING" | "WARN" => Ok(LogLevel::Warning),
+            "ERROR" => Ok(LogLevel::Error),
+            "CRITICAL" | "FATAL" => Ok(LogLevel::Critical),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A line of an Airflow task log, shaped as `[timestamp] {file.py:line} LEVEL - message`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogLine<'a> {
+    pub timestamp: &'a str,
+    pub source: &'a str,
+    pub level: LogLevel,
+    pub message: &'a str,
+}
+
+impl<'a> LogLine<'a> {
+    /// Parse `line`, `None` if it is not shaped as an Airflow log line, e.g. a traceback line.
+    pub fn parse(line: &'a str) -> Option<Self> {
+        let (timestamp, rest) = line.strip_prefix('[')?.split_once("] ")?;
+        let (source, rest) = rest.strip_prefix('{')?.split_once("} ")?;
+        let (level, message) = rest.split_once(" - ").unwrap_or((rest, ""));
+        Some(Self {
+            timestamp,
+            source,
+            level: level.trim().parse().ok()?,
+            message,
+        })
+    }
+
+    /// Whether `line` is one of the `***` headers written by Airflow before the log of each
+    /// host, e.g. `*** Reading local file: ...`.
+    pub fn is_header(line: &str) -> bool {
+        line.starts_with("***")
+    }
+}
+
+#[cfg(test)]
+mod tests;
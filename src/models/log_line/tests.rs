@@ -0,0 +1,59 @@
+use super::*;
+
+#[test]
+fn parse_a_log_line() {
+    assert_eq!(
+        LogLine::parse(
+            "[2024-03-12, 00:01:03 UTC] {taskinstance.py:2699} ERROR - Task failed - exit 1"
+        ),
+        Some(LogLine {
+            timestamp: "2024-03-12, 00:01:03 UTC",
+            source: "taskinstance.py:2699",
+            level: LogLevel::Error,
+            message: "Task failed - exit 1",
+        })
+    );
+}
+
+#[test]
+fn parse_the_levels() {
+    for (level, expected) in [
+        ("DEBUG", LogLevel::Debug),
+        ("INFO", LogLevel::Info),
+        ("WARN", LogLevel::Warning),
+        ("WARNING", LogLevel::Warning),
+        ("FATAL", LogLevel::Critical),
+        ("CRITICAL", LogLevel::Critical),
+    ] {
+        let line = format!("[2024-03-12] {{dag.py:1}} {} - message", level);
+        assert_eq!(LogLine::parse(&line).map(|line| line.level), Some(expected));
+    }
+    assert!(LogLevel::Debug < LogLevel::Info && LogLevel::Error < LogLevel::Critical);
+}
+
+#[test]
+fn parse_a_line_without_message() {
+    let line = LogLine::parse("[2024-03-12] {dag.py:1} INFO").unwrap();
+    assert_eq!(line.level, LogLevel::Info);
+    assert_eq!(line.message, "");
+}
+
+#[test]
+fn parse_other_lines() {
+    for line in [
+        "Traceback (most recent call last):",
+        "  File \"/opt/airflow/dags/dag.py\", line 1, in <module>",
+        "[2024-03-12] {dag.py:1} NOTICE - unknown level",
+        "[2024-03-12] INFO - no source",
+        "*** Found local files:",
+        "",
+    ] {
+        assert_eq!(LogLine::parse(line), None, "{}", line);
+    }
+}
+
+#[test]
+fn headers() {
+    assert!(LogLine::is_header("*** Found local files:"));
+    assert!(!LogLine::is_header("[2024-03-12] {dag.py:1} INFO - ***"));
+}
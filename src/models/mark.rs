@@ -0,0 +1,45 @@
+use serde::Serialize;
+use strum::Display;
+
+/// State a dag run or a task instance can be marked as.
+#[derive(Serialize, Display, Debug, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum MarkState {
+    #[default]
+    Success,
+    Failed,
+}
+
+/// Mark of a whole dag run, or of a single task instance and optionally its relatives.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MarkRequest {
+    pub dag_id: String,
+    pub dag_run_id: String,
+    pub task_id: Option<String>,
+    pub state: MarkState,
+    pub include_upstream: bool,
+    pub include_downstream: bool,
+}
+
+impl MarkRequest {
+    /// Mark a dag run, its unfinished task instances are marked too by Airflow.
+    pub fn dag_run(dag_id: &str, dag_run_id: &str, state: MarkState) -> Self {
+        Self {
+            dag_id: dag_id.to_string(),
+            dag_run_id: dag_run_id.to_string(),
+            task_id: None,
+            state,
+            include_upstream: false,
+            include_downstream: false,
+        }
+    }
+
+    /// Mark a single task instance of a dag run.
+    pub fn task(dag_id: &str, dag_run_id: &str, task_id: &str, state: MarkState) -> Self {
+        Self {
+            task_id: Some(task_id.to_string()),
+            ..Self::dag_run(dag_id, dag_run_id, state)
+        }
+    }
+}
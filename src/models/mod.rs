@@ -1,7 +1,15 @@
+pub mod clear;
 pub mod conf;
 pub mod dag;
 pub mod dag_run;
 pub mod dag_runs;
-mod log;
-mod task;
+pub mod dags;
+mod de;
+pub mod log;
+pub mod log_line;
+pub mod mark;
+mod pool;
+pub mod pools;
+pub mod task;
 pub mod tasks;
+pub mod version;
@@ -0,0 +1,22 @@
+use crate::models::de::null_as_default;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(default)]
+pub struct Pool {
+    pub(crate) name: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub(crate) slots: i64,
+    #[serde(deserialize_with = "null_as_default")]
+    pub(crate) occupied_slots: i64,
+    #[serde(deserialize_with = "null_as_default")]
+    pub(crate) running_slots: i64,
+    #[serde(deserialize_with = "null_as_default")]
+    pub(crate) queued_slots: i64,
+    #[serde(deserialize_with = "null_as_default")]
+    pub(crate) open_slots: i64,
+    scheduled_slots: Option<i64>,
+    deferred_slots: Option<i64>,
+    description: Option<String>,
+    include_deferred: Option<bool>,
+}
@@ -0,0 +1,41 @@
+use crate::models::de::skip_invalid;
+use crate::models::pool::Pool;
+use ratatui::widgets::Row;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Pools {
+    #[serde(deserialize_with = "skip_invalid")]
+    pub(crate) pools: Vec<Pool>,
+    #[serde(default)]
+    total_entries: u32,
+}
+
+impl Pools {
+    pub fn new() -> Self {
+        Self {
+            pools: vec![],
+            total_entries: 0,
+        }
+    }
+
+    pub fn get_total_entries(&self) -> u32 {
+        self.total_entries
+    }
+
+    pub fn get_pools_row(&self) -> Vec<Row<'_>> {
+        let mut rows: Vec<Row> = Vec::new();
+
+        for pool in &self.pools {
+            rows.push(Row::new(vec![
+                pool.name.clone(),
+                pool.slots.to_string(),
+                pool.running_slots.to_string(),
+                pool.queued_slots.to_string(),
+                pool.occupied_slots.to_string(),
+                pool.open_slots.to_string(),
+            ]));
+        }
+        rows
+    }
+}
@@ -1,133 +1,103 @@
-use crate::config::Airflow;
-use crate::models::log::Log;
+use crate::models::clear::ClearRequest;
+use crate::models::de::null_as_default;
+use crate::models::mark::{MarkRequest, MarkState};
 use color_eyre::eyre::Result;
-use reqwest::Client;
 use serde::Deserialize;
 use serde_json::json;
 use serde_json::Value;
 use std::collections::HashMap;
 
-#[derive(Debug, Default, Deserialize)]
+/// A task instance. Every field but the identifiers is optional, as the fields differ between
+/// Airflow versions.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
 pub struct Task {
-    dag_id: String,
-    dag_run_id: String,
+    pub(crate) dag_id: String,
+    pub(crate) dag_run_id: String,
     pub(crate) duration: Option<f64>,
     end_date: Option<String>,
-    execution_date: String,
-    executor_config: String,
-    hostname: String,
-    map_index: i8,
-    max_tries: i8,
+    execution_date: Option<String>,
+    executor_config: Value,
+    hostname: Option<String>,
+    map_index: Option<i64>,
+    max_tries: Option<i64>,
     note: Option<String>,
     pub(crate) operator: Option<String>,
-    pid: Option<i32>,
-    pool: String,
-    pool_slots: i16,
-    priority_weight: Option<i32>,
+    pid: Option<i64>,
+    pool: Option<String>,
+    pool_slots: Option<i64>,
+    priority_weight: Option<i64>,
     queue: Option<String>,
     queued_when: Option<String>,
-    rendered_fields: Option<RenderedFields>,
+    rendered_fields: Value,
     sla_miss: Option<SlaMiss>,
     start_date: Option<String>,
     pub(crate) state: Option<String>,
     pub(crate) task_id: String,
     trigger: Option<Trigger>,
     triggerer_job: Option<TriggerJob>,
-    pub(crate) try_number: f64,
-    unixname: String,
+    #[serde(deserialize_with = "null_as_default")]
+    pub(crate) try_number: u32,
+    unixname: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
 pub struct SlaMiss {
-    dag_id: String,
-    description: String,
-    email_sent: bool,
-    execution_date: String,
-    notification_sent: bool,
-    task_id: String,
-    timestamp: String,
+    dag_id: Option<String>,
+    description: Option<String>,
+    email_sent: Option<bool>,
+    execution_date: Option<String>,
+    notification_sent: Option<bool>,
+    task_id: Option<String>,
+    timestamp: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
 pub struct Trigger {
-    classpath: String,
-    created_date: String,
-    id: i32,
-    kwargs: String,
-    triggerer_id: i32,
+    classpath: Option<String>,
+    created_date: Option<String>,
+    id: Option<i64>,
+    kwargs: Option<String>,
+    triggerer_id: Option<i64>,
 }
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
 pub struct TriggerJob {
-    dag_id: String,
-    end_date: String,
-    executor_class: String,
-    hostname: String,
-    id: i32,
-    job_type: String,
-    latest_heartbeat: String,
-    start_date: String,
-    state: String,
-    unixname: String,
+    dag_id: Option<String>,
+    end_date: Option<String>,
+    executor_class: Option<String>,
+    hostname: Option<String>,
+    id: Option<i64>,
+    job_type: Option<String>,
+    latest_heartbeat: Option<String>,
+    start_date: Option<String>,
+    state: Option<String>,
+    unixname: Option<String>,
 }
 
-#[derive(Debug, Default, Deserialize)]
-pub struct RenderedFields {}
-
 impl Task {
-    pub async fn clear(
-        &mut self,
-        client: &Client,
-        cfg_airflow: &Airflow,
-        username: &str,
-        password: &str,
-        url: &str,
-    ) -> Result<()> {
-        let body = json!({
-            "dry_run": true,
-            "task_ids": [self.task_id],
-            "only_failed": true,
-            "only_running": false,
-            "include_subdags": true,
-            "include_parentdag": true,
-            "reset_dag_runs": true,
-            "dag_run_id": self.dag_run_id,
-            "include_upstream": false,
-            "include_downstream": false,
-            "include_future": false,
-            "include_past": false
-        });
-        let task = client
-            .post(format!(
-                "{}/api/v1/dags/{}/clearTaskInstance",
-                &cfg_airflow.host, self.dag_id
-            ))
-            .basic_auth(&cfg_airflow.username, Some(&cfg_airflow.password))
-            .json(&body)
-            .send()
-            .await?;
-        Ok(())
+    pub fn clear_request(&self) -> ClearRequest {
+        ClearRequest::task(&self.dag_id, &self.dag_run_id, &self.task_id)
+    }
+
+    pub fn mark_request(&self, state: MarkState) -> MarkRequest {
+        MarkRequest::task(&self.dag_id, &self.dag_run_id, &self.task_id, state)
+    }
+
+    /// The index of the task instance among the instances of a mapped task, `None` if the
+    /// task is not mapped.
+    pub fn get_map_index(&self) -> Option<i64> {
+        self.map_index.filter(|map_index| *map_index >= 0)
     }
 
-    pub async fn get_logs(
-        &mut self,
-        client: &Client,
-        cfg_airflow: &Airflow,
-        username: &str,
-        password: &str,
-        url: &str,
-        try_number: usize,
-    ) -> Result<String> {
-        let logs = client
-            .get(format!(
-                "{}/api/v1/dags/{}/dagRuns/{}/taskInstances/{}/logs/{}",
-                &cfg_airflow.host, self.dag_id, self.dag_run_id, self.task_id, try_number
-            ))
-            .basic_auth(&cfg_airflow.username, Some(&cfg_airflow.password))
-            .send()
-            .await?
-            .text()
-            .await?;
-        Ok(logs)
+    /// Whether `other` is the same task instance, whatever state it was fetched in.
+    pub fn is_same_instance(&self, other: &Task) -> bool {
+        self.dag_id == other.dag_id
+            && self.dag_run_id == other.dag_run_id
+            && self.task_id == other.task_id
+            && self.map_index == other.map_index
     }
 }
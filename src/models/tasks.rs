@@ -1,15 +1,16 @@
-use crate::config::Airflow;
 use crate::models::dag_runs::DagRuns;
+use crate::models::de::skip_invalid;
 use crate::models::task::Task;
 use crate::style;
 use color_eyre::eyre::Result;
 use ratatui::widgets::Row;
-use reqwest::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
 pub struct Tasks {
+    #[serde(deserialize_with = "skip_invalid")]
     pub task_instances: Vec<Task>,
+    #[serde(default)]
     pub total_entries: u32,
 }
 
@@ -21,38 +22,54 @@ impl Tasks {
         })
     }
 
+    /// The task instance of `task_id`.
+    pub fn get_task(&self, task_id: &str) -> Option<&Task> {
+        self.task_instances
+            .iter()
+            .find(|task| task.task_id == task_id)
+    }
+
     pub fn get_total_entries(&self) -> u32 {
         self.total_entries
     }
 
-    pub fn get_tasks_row(&self) -> Vec<Row> {
-        let mut rows: Vec<Row> = Vec::new();
+    /// The task instances displayed in the table.
+    pub fn get_records(&self) -> Vec<TaskRecord> {
+        self.task_instances
+            .iter()
+            .map(|task| TaskRecord {
+                operator: task.operator.clone().unwrap_or("n/a".to_string()),
+                task_id: task.task_id.clone(),
+                try_number: task.try_number,
+                state: task.state.clone().unwrap_or("n/a".to_string()),
+                duration: task.duration.unwrap_or(0.0).max(0.0),
+            })
+            .collect()
+    }
 
-        for task in &self.task_instances {
-            rows.push(
+    pub fn get_tasks_row(&self) -> Vec<Row<'_>> {
+        self.get_records()
+            .into_iter()
+            .map(|record| {
                 Row::new(vec![
-                    task.operator.clone().unwrap_or("n/a".to_string()),
-                    task.task_id.clone(),
-                    task.try_number.to_string(),
-                    task.state.clone().unwrap_or("n/a".to_string()),
-                    format!(
-                        "{:.2} seconds",
-                        if let Some(duration) = task.duration {
-                            if duration > 0.0 {
-                                duration
-                            } else {
-                                0.0
-                            }
-                        } else {
-                            0.0
-                        }
-                    ),
+                    record.operator,
+                    record.task_id,
+                    record.try_number.to_string(),
+                    record.state.clone(),
+                    format!("{:.2} seconds", record.duration),
                 ])
-                .style(style::get_style_row(
-                    &task.state.clone().unwrap_or_default(),
-                )),
-            );
-        }
-        rows
+                .style(style::get_style_row(&record.state))
+            })
+            .collect()
     }
 }
+
+/// A task instance as displayed in the table, independently of how it is rendered.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct TaskRecord {
+    pub operator: String,
+    pub task_id: String,
+    pub try_number: u32,
+    pub state: String,
+    pub duration: f64,
+}
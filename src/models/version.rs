@@ -0,0 +1,61 @@
+use std::fmt;
+use std::str::FromStr;
+
+use color_eyre::eyre::{eyre, Report};
+use serde::Deserialize;
+
+/// Version of an Airflow webserver, e.g. `2.8.3`. The pre-release suffixes are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AirflowVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+// First version with the data interval and the logical date of the dag runs
+pub const DATA_INTERVALS: AirflowVersion = AirflowVersion::new(2, 2, 0);
+// First version with notes on the dag runs and the task instances
+pub const NOTES: AirflowVersion = AirflowVersion::new(2, 5, 0);
+
+impl AirflowVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+}
+
+impl FromStr for AirflowVersion {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Only the leading digits count, e.g. in `2.10.0rc1` or `2.9.0.dev0`
+        let mut numbers = s.trim().split('.').map(|part| {
+            let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse::<u32>().ok()
+        });
+        match (numbers.next().flatten(), numbers.next().flatten()) {
+            (Some(major), Some(minor)) => Ok(Self::new(
+                major,
+                minor,
+                numbers.next().flatten().unwrap_or(0),
+            )),
+            _ => Err(eyre!("Unknown Airflow version `{}`", s)),
+        }
+    }
+}
+
+impl fmt::Display for AirflowVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Body of `/api/v1/version`.
+#[derive(Deserialize, Debug, Default)]
+pub(crate) struct VersionInfo {
+    pub(crate) version: String,
+    pub(crate) git_version: Option<String>,
+}
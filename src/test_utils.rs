@@ -0,0 +1,15 @@
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+
+/// Response recorded from an Airflow 2.8 webserver, under `tests/fixtures`.
+pub fn fixture(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+pub fn fixture_json<T: DeserializeOwned>(name: &str) -> T {
+    serde_json::from_str(&fixture(name)).unwrap()
+}
@@ -230,8 +230,8 @@ impl Tui {
 
     pub fn get_main_constraint(&self, mode: Mode) -> [Constraint; 4] {
         [
-            Constraint::Length(6),
-            if mode == Mode::Search || mode == Mode::Command {
+            Constraint::Length(7),
+            if matches!(mode, Mode::Search | Mode::TextSearch | Mode::Command) {
                 Constraint::Length(3)
             } else {
                 Constraint::Percentage(0)
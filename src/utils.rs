@@ -158,6 +158,32 @@ pub fn get_user_input_by_key(key: KeyCode, target: &mut Option<String>) {
     }
 }
 
+/// Open `content` in the user's `$EDITOR` (`vi` by default) and return the edited content,
+/// `None` if it was left unchanged. The file is named `arvz-<random><suffix>`, e.g. with a
+/// `.json5` suffix for the editor to highlight it.
+/// The terminal must be released by the TUI before calling this function.
+pub fn edit_in_editor(suffix: &str, content: &str) -> Result<Option<String>> {
+    // Only readable by the user, and removed once dropped
+    let file = tempfile::Builder::new()
+        .prefix(concat!(env!("CARGO_PKG_NAME"), "-"))
+        .suffix(suffix)
+        .tempfile()?;
+    std::fs::write(file.path(), content)?;
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| String::from("vi"));
+    // Run by the shell, for the editor to be given with arguments, e.g. `code --wait`
+    let status = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg("sh")
+        .arg(file.path())
+        .status()?;
+    if !status.success() {
+        return Err(color_eyre::eyre::eyre!("{} exited with an error", editor));
+    }
+    let edited = std::fs::read_to_string(file.path())?;
+    Ok(Some(edited).filter(|edited| edited != content))
+}
+
 /// Similar to the `std::dbg!` macro, but generates `tracing` events rather
 /// than printing to stdout.
 ///
@@ -201,3 +227,6 @@ Config directory: {config_dir_path}
 Data directory: {data_dir_path}"
     )
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,17 @@
+use super::*;
+
+// The only test reading `EDITOR`, so that setting it does not race with another test
+#[test]
+fn edit_in_editor_with_arguments() {
+    std::env::set_var("EDITOR", "sed -i -e s/null/true/");
+    assert_eq!(
+        edit_in_editor(".json5", "{ note: null }").unwrap(),
+        Some(String::from("{ note: true }"))
+    );
+
+    std::env::set_var("EDITOR", "sed -i -e s/missing/found/");
+    assert_eq!(edit_in_editor(".json5", "{ note: null }").unwrap(), None);
+
+    std::env::set_var("EDITOR", "false");
+    assert!(edit_in_editor(".json5", "{ note: null }").is_err());
+}
@@ -0,0 +1,554 @@
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use color_eyre::eyre::{eyre, Result};
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+use crate::action::Action;
+use crate::client::{AirflowClient, LOG_TIMEOUT};
+use crate::config::Config;
+use crate::models::clear::ClearRequest;
+use crate::models::dag::Dag;
+use crate::models::dag_run::{DagRun, DagRunTrigger};
+use crate::models::dag_runs::DagRuns;
+use crate::models::dags::Dags;
+use crate::models::mark::MarkRequest;
+use crate::models::task::Task;
+
+// Delay between two refreshes of the dag runs
+const DAG_RUNS_INTERVAL: Duration = Duration::from_secs(3);
+// Delay between two refreshes of the dags
+const DAGS_INTERVAL: Duration = Duration::from_secs(10);
+// Delay between two refreshes of the task instances of the watched dag run
+const TASKS_INTERVAL: Duration = Duration::from_secs(2);
+// Delay between two fetches of the new content of a followed log
+const LOG_INTERVAL: Duration = Duration::from_secs(2);
+// Maximum time given to the webserver to answer a polling request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `Worker` polls the Airflow webserver in background tokio tasks so that a slow or
+/// unreachable webserver never blocks the event loop.
+/// Results are sent back to the application as actions.
+pub struct Worker {
+    client: AirflowClient,
+    page_size: u32,
+    action_tx: UnboundedSender<Action>,
+    cancellation_token: CancellationToken,
+    dag_runs_cancellation_token: Option<CancellationToken>,
+    // Dag whose runs are polled, every dag if `None`
+    watched_dag: Option<String>,
+    tasks_cancellation_token: Option<CancellationToken>,
+    watched_dag_run: Option<(String, String)>,
+    log_cancellation_token: Option<CancellationToken>,
+}
+
+impl Worker {
+    pub fn new(client: AirflowClient, page_size: u32, action_tx: UnboundedSender<Action>) -> Self {
+        Self {
+            client,
+            page_size,
+            action_tx,
+            cancellation_token: CancellationToken::new(),
+            dag_runs_cancellation_token: None,
+            watched_dag: None,
+            tasks_cancellation_token: None,
+            watched_dag_run: None,
+            log_cancellation_token: None,
+        }
+    }
+
+    /// Start polling the first page of the dag runs of `dag_id`, of every dag if `None`.
+    /// A `DagRunsLoaded` action tagged with `dag_id` is sent after each refresh.
+    /// The dag runs previously watched are no longer polled.
+    pub fn watch_dag_runs(&mut self, dag_id: Option<String>) {
+        if let Some(cancellation_token) = self.dag_runs_cancellation_token.take() {
+            cancellation_token.cancel();
+        }
+        let cancellation_token = self.cancellation_token.child_token();
+        self.dag_runs_cancellation_token = Some(cancellation_token.clone());
+        self.watched_dag = dag_id.clone();
+
+        let client = self.client.clone();
+        let page_size = self.page_size;
+        spawn_polling(
+            cancellation_token,
+            self.action_tx.clone(),
+            DAG_RUNS_INTERVAL,
+            move || {
+                let client = client.clone();
+                let dag_id = dag_id.clone();
+                async move {
+                    let dag_runs = client
+                        .get_dag_runs(dag_id.as_deref(), &[], page_size, 0)
+                        .await?;
+                    Ok(Action::DagRunsLoaded(dag_id, dag_runs))
+                }
+            },
+        );
+    }
+
+    /// The dag whose runs are polled, `None` for every dag.
+    pub fn get_watched_dag(&self) -> Option<&str> {
+        self.watched_dag.as_deref()
+    }
+
+    /// Start polling the dags, a `DagsLoaded` action is sent after each refresh.
+    pub fn watch_dags(&self) {
+        let client = self.client.clone();
+        spawn_polling(
+            self.cancellation_token.child_token(),
+            self.action_tx.clone(),
+            DAGS_INTERVAL,
+            move || {
+                let client = client.clone();
+                async move {
+                    let dags = client.get_dags().await?;
+                    Ok(Action::DagsLoaded(dags))
+                }
+            },
+        );
+    }
+
+    /// Ask the version of the Airflow webserver, a `VersionDetected` action is sent once known.
+    pub fn detect_version(&self) {
+        let client = self.client.clone();
+        self.spawn_request("detect the Airflow version", async move {
+            let version = client.get_version().await?;
+            Ok(Action::VersionDetected(version.to_string()))
+        });
+    }
+
+    /// Activate the context `name` of a copy of `config` and build its client, a
+    /// `ContextSwitched` action is sent with both once ready. The secrets of the context may be
+    /// read from a command, they are resolved out of the async runtime.
+    pub fn switch_context(&self, mut config: Config, name: String) {
+        self.spawn_request("switch context", async move {
+            tokio::task::spawn_blocking(move || {
+                config.use_context(&name).map_err(|e| eyre!(e))?;
+                let client = AirflowClient::new(&config.airflow)?;
+                Ok(Action::ContextSwitched(Box::new(config), Box::new(client)))
+            })
+            .await?
+        });
+    }
+
+    /// Fetch the source code of `dag_id`, a `CodeLoaded` action tagged with the dag is sent
+    /// once loaded.
+    pub fn load_dag_source(&self, dag_id: String) {
+        let client = self.client.clone();
+        self.spawn_request("load source code", async move {
+            let code = client.get_dag_source(&dag_id).await?;
+            Ok(Action::CodeLoaded(dag_id, code))
+        });
+    }
+
+    /// Fetch the pools, a `PoolsLoaded` action is sent once loaded.
+    pub fn load_pools(&self) {
+        let client = self.client.clone();
+        self.spawn_request("load pools", async move {
+            let pools = client.get_pools().await?;
+            Ok(Action::PoolsLoaded(pools))
+        });
+    }
+
+    /// Fetch the whole log of `try_number` of `task`, a `LogLoaded` action tagged with the
+    /// task instance and the try is sent once loaded.
+    pub fn load_log(&self, task: Task, try_number: usize) {
+        let client = self.client.clone();
+        self.spawn_request_within(
+            "load log",
+            LOG_TIMEOUT,
+            async move {
+                let log = client.get_log(&task, try_number).await?;
+                Ok(Action::LogLoaded(Box::new(task), try_number, log))
+            },
+            Action::Error,
+        );
+    }
+
+    /// Fetch the current state of `dag_id` alone, the polled dags may not be loaded yet, and
+    /// send a `Confirm` action to unpause it if paused, to pause it otherwise.
+    pub fn toggle_dag_paused(&self, dag_id: String) {
+        let client = self.client.clone();
+        self.spawn_request("load dag", async move {
+            let dag = client.get_dag(&dag_id).await?;
+            let is_paused = dag.is_paused();
+            Ok(Action::Confirm(
+                format!(
+                    "{} dag {} ?",
+                    if is_paused { "Unpause" } else { "Pause" },
+                    dag_id
+                ),
+                Box::new(Action::SetDagPaused(dag_id, !is_paused)),
+            ))
+        });
+    }
+
+    /// Pause or unpause `dag_id`, a `DagUpdated` action is sent once updated.
+    pub fn set_dag_paused(&self, dag_id: String, is_paused: bool) {
+        let client = self.client.clone();
+        self.spawn_request("update dag", async move {
+            let dag = client.set_dag_paused(&dag_id, is_paused).await?;
+            Ok(Action::DagUpdated(Box::new(dag)))
+        });
+    }
+
+    /// Fetch the page of the watched dag runs starting at `offset`, a `DagRunsPageLoaded`
+    /// action tagged with the watched dag is sent once loaded, a `DagRunsPageFailed` action
+    /// otherwise.
+    pub fn load_dag_runs_page(&self, offset: u32) {
+        let client = self.client.clone();
+        let page_size = self.page_size;
+        let dag_id = self.watched_dag.clone();
+        self.spawn_request_or(
+            "load dag runs",
+            async move {
+                let dag_runs = client
+                    .get_dag_runs(dag_id.as_deref(), &[], page_size, offset)
+                    .await?;
+                Ok(Action::DagRunsPageLoaded(dag_id, dag_runs))
+            },
+            Action::DagRunsPageFailed,
+        );
+    }
+
+    /// Trigger a new run of `dag_id`, a `DagRunTriggered` action is sent once created.
+    pub fn trigger_dag_run(&self, dag_id: String, trigger: DagRunTrigger) {
+        let client = self.client.clone();
+        self.spawn_request("trigger dag run", async move {
+            let dag_run = client.trigger_dag_run(&dag_id, &trigger).await?;
+            Ok(Action::DagRunTriggered(Box::new(dag_run)))
+        });
+    }
+
+    /// Dry run `request`, a `ClearPreviewLoaded` action is sent with the task instances that
+    /// would be cleared, a `ClearPreviewFailed` action tagged with `request` otherwise.
+    pub fn preview_clear(&self, request: ClearRequest) {
+        let client = self.client.clone();
+        let failed_request = Box::new(request.clone());
+        self.spawn_request_or(
+            "preview clear",
+            async move {
+                let task_instances = client.clear_task_instances(&request, true).await?;
+                Ok(Action::ClearPreviewLoaded(
+                    Box::new(request),
+                    task_instances,
+                ))
+            },
+            move |message| Action::ClearPreviewFailed(failed_request, message),
+        );
+    }
+
+    /// Clear the task instances targeted by `request`.
+    pub fn clear(&self, request: ClearRequest) {
+        let client = self.client.clone();
+        self.spawn_request("clear", async move {
+            let task_instances = client.clear_task_instances(&request, false).await?;
+            Ok(Action::Info(format!(
+                "Cleared {} task instance(s)",
+                task_instances.len()
+            )))
+        });
+    }
+
+    /// Mark the dag run or the task instances targeted by `request`.
+    pub fn mark(&self, request: MarkRequest) {
+        let client = self.client.clone();
+        self.spawn_request("mark", async move {
+            client.mark(&request).await?;
+            let target = request.task_id.as_ref().unwrap_or(&request.dag_run_id);
+            Ok(Action::Info(format!(
+                "Marked {} as {}",
+                target, request.state
+            )))
+        });
+    }
+
+    /// Start polling the task instances of `dag_run`, a `TasksLoaded` action tagged with the
+    /// dag run is sent after each refresh. Any previously watched dag run is no longer polled.
+    ///
+    /// Returns `false` if `dag_run` was already watched.
+    pub fn watch_tasks(&mut self, dag_run: DagRun) -> bool {
+        let watched_dag_run = (dag_run.dag_id.clone(), dag_run.dag_run_id.clone());
+        if self.watched_dag_run.as_ref() == Some(&watched_dag_run) {
+            return false;
+        }
+        self.unwatch_tasks();
+        let cancellation_token = self.cancellation_token.child_token();
+        self.tasks_cancellation_token = Some(cancellation_token.clone());
+        self.watched_dag_run = Some(watched_dag_run);
+
+        let client = self.client.clone();
+        spawn_polling(
+            cancellation_token,
+            self.action_tx.clone(),
+            TASKS_INTERVAL,
+            move || {
+                let client = client.clone();
+                let dag_run = dag_run.clone();
+                async move {
+                    let tasks = client
+                        .get_task_instances(&dag_run.dag_id, &dag_run.dag_run_id)
+                        .await?;
+                    Ok(Action::TasksLoaded(
+                        dag_run.dag_id,
+                        dag_run.dag_run_id,
+                        tasks,
+                    ))
+                }
+            },
+        );
+        true
+    }
+
+    /// Whether the task instances of `dag_run_id` of `dag_id` are polled.
+    pub fn is_watching_tasks(&self, dag_id: &str, dag_run_id: &str) -> bool {
+        self.watched_dag_run
+            .as_ref()
+            .is_some_and(|(watched_dag_id, watched_dag_run_id)| {
+                watched_dag_id == dag_id && watched_dag_run_id == dag_run_id
+            })
+    }
+
+    /// Stop polling the task instances of the watched dag run.
+    pub fn unwatch_tasks(&mut self) {
+        if let Some(cancellation_token) = self.tasks_cancellation_token.take() {
+            cancellation_token.cancel();
+        }
+        self.watched_dag_run = None;
+    }
+
+    /// Write the logs of every try of every task instance of `dag_run` under `dir`, as
+    /// `dag_id/run_id/task_id/try_n.log`, or `dag_id/run_id/task_id/map_n/try_n.log` for the
+    /// instances of a mapped task. The progress is reported with `Info` actions.
+    pub fn export_logs(&self, dag_run: DagRun, dir: PathBuf) {
+        let client = self.client.clone();
+        let progress_tx = self.action_tx.clone();
+        let cancellation_token = self.cancellation_token.child_token();
+        let action_tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            let export = export_logs(&client, &dag_run, &dir, |message| {
+                let _ = progress_tx.send(Action::Info(message));
+            });
+            let action = tokio::select! {
+                _ = cancellation_token.cancelled() => return,
+                result = export => match result {
+                    Ok(message) => Action::Info(message),
+                    Err(e) => Action::Error(format!("Failed to export logs: {}", e)),
+                },
+            };
+            let _ = action_tx.send(action);
+        });
+    }
+
+    /// Start following the log of `try_number` of `task`, from its beginning.
+    /// A `LogAppended` action is sent with the content written since the previous fetch, and
+    /// whether the log has ended.
+    /// Any previously followed log is no longer fetched.
+    pub fn follow_log(&mut self, task: Task, try_number: usize) {
+        self.unfollow_log();
+        let cancellation_token = self.cancellation_token.child_token();
+        self.log_cancellation_token = Some(cancellation_token.clone());
+
+        let client = self.client.clone();
+        // The token is only moved forward once a chunk was fetched, a failed fetch is retried
+        let continuation_token: Arc<Mutex<Option<String>>> = Arc::default();
+        spawn_polling(
+            cancellation_token,
+            self.action_tx.clone(),
+            LOG_INTERVAL,
+            move || {
+                let client = client.clone();
+                let task = task.clone();
+                let continuation_token = continuation_token.clone();
+                async move {
+                    let token = continuation_token.lock().unwrap().clone();
+                    let log = client
+                        .get_log_chunk(&task, try_number, token.as_deref())
+                        .await?;
+                    let action = Action::LogAppended(log.get_text(), log.is_end_of_log());
+                    if log.continuation_token.is_some() {
+                        *continuation_token.lock().unwrap() = log.continuation_token;
+                    }
+                    Ok(action)
+                }
+            },
+        );
+    }
+
+    /// Stop following the log.
+    pub fn unfollow_log(&mut self) {
+        if let Some(cancellation_token) = self.log_cancellation_token.take() {
+            cancellation_token.cancel();
+        }
+    }
+
+    /// Run `request` once in the background, bounded by `REQUEST_TIMEOUT`.
+    /// A failure is reported as `Action::Error` describing `what` failed.
+    fn spawn_request<Fut>(&self, what: &'static str, request: Fut)
+    where
+        Fut: Future<Output = Result<Action>> + Send + 'static,
+    {
+        self.spawn_request_or(what, request, Action::Error);
+    }
+
+    /// Run `request` once in the background like `spawn_request`, a failure is reported as
+    /// the action built by `failed` from the error message.
+    fn spawn_request_or<Fut, F>(&self, what: &'static str, request: Fut, failed: F)
+    where
+        Fut: Future<Output = Result<Action>> + Send + 'static,
+        F: FnOnce(String) -> Action + Send + 'static,
+    {
+        self.spawn_request_within(what, REQUEST_TIMEOUT, request, failed);
+    }
+
+    /// Run `request` once in the background like `spawn_request_or`, bounded by `timeout`.
+    fn spawn_request_within<Fut, F>(
+        &self,
+        what: &'static str,
+        timeout: Duration,
+        request: Fut,
+        failed: F,
+    ) where
+        Fut: Future<Output = Result<Action>> + Send + 'static,
+        F: FnOnce(String) -> Action + Send + 'static,
+    {
+        let cancellation_token = self.cancellation_token.child_token();
+        let action_tx = self.action_tx.clone();
+        tokio::spawn(async move {
+            let action = tokio::select! {
+                _ = cancellation_token.cancelled() => return,
+                result = tokio::time::timeout(timeout, request) => match result {
+                    Ok(Ok(action)) => action,
+                    Ok(Err(e)) => failed(format!("Failed to {}: {}", what, e)),
+                    Err(_) => failed(String::from("Airflow did not answer in time")),
+                },
+            };
+            let _ = action_tx.send(action);
+        });
+    }
+
+    /// Stop every polling task.
+    pub fn cancel(&self) {
+        self.cancellation_token.cancel();
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+/// Write the logs of `dag_run` under `dir` as described by `Worker::export_logs`, calling
+/// `progress` after each try. A try whose log cannot be fetched is counted as failed and the
+/// export goes on, the summary of the export is returned.
+async fn export_logs(
+    client: &AirflowClient,
+    dag_run: &DagRun,
+    dir: &Path,
+    progress: impl Fn(String),
+) -> Result<String> {
+    // Each request is bounded by the client, not the whole export
+    let tasks = client
+        .get_task_instances(&dag_run.dag_id, &dag_run.dag_run_id)
+        .await?;
+    let run_dir = dir
+        .join(path_component(&dag_run.dag_id))
+        .join(path_component(&dag_run.dag_run_id));
+    let total: usize = tasks
+        .task_instances
+        .iter()
+        .map(|task| task.try_number as usize)
+        .sum();
+    let mut exported = 0;
+    let mut failed = 0;
+    for task in tasks.task_instances {
+        let mut task_dir = run_dir.join(path_component(&task.task_id));
+        if let Some(map_index) = task.get_map_index() {
+            task_dir = task_dir.join(format!("map_{}", map_index));
+        }
+        tokio::fs::create_dir_all(&task_dir).await?;
+        for try_number in 1..=task.try_number as usize {
+            match client.get_log(&task, try_number).await {
+                Ok(log) => {
+                    tokio::fs::write(task_dir.join(format!("try_{}.log", try_number)), log).await?;
+                    exported += 1;
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Cannot export try {} of {}: {}",
+                        try_number,
+                        task.task_id,
+                        e
+                    );
+                    failed += 1;
+                }
+            }
+            let done = exported + failed;
+            progress(if failed == 0 {
+                format!("Exporting logs {}/{}", done, total)
+            } else {
+                format!("Exporting logs {}/{}, {} failed", done, total, failed)
+            });
+        }
+    }
+    Ok(if failed == 0 {
+        format!("Exported {} logs to {}", exported, run_dir.display())
+    } else {
+        format!(
+            "Exported {} logs to {}, {} could not be fetched",
+            exported,
+            run_dir.display(),
+            failed
+        )
+    })
+}
+
+/// `name` usable as a single path component, e.g. a dag run id containing a `/`.
+fn path_component(name: &str) -> String {
+    name.replace(['/', '\\'], "_")
+}
+
+/// Spawn a task calling `fetch` every `interval` until `cancellation_token` is cancelled.
+/// Each call is bounded by `REQUEST_TIMEOUT`, failures are reported as `Action::Error`.
+fn spawn_polling<F, Fut>(
+    cancellation_token: CancellationToken,
+    action_tx: UnboundedSender<Action>,
+    interval: Duration,
+    fetch: F,
+) -> JoinHandle<()>
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<Action>> + Send,
+{
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                _ = cancellation_token.cancelled() => break,
+                _ = interval.tick() => {}
+            }
+            let action = tokio::select! {
+                _ = cancellation_token.cancelled() => break,
+                result = tokio::time::timeout(REQUEST_TIMEOUT, fetch()) => match result {
+                    Ok(Ok(action)) => action,
+                    Ok(Err(e)) => Action::Error(format!("Failed to poll airflow: {}", e)),
+                    Err(_) => Action::Error(String::from("Airflow did not answer in time")),
+                },
+            };
+            // The result is dropped if the polling was cancelled while fetching
+            if cancellation_token.is_cancelled() || action_tx.send(action).is_err() {
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests;
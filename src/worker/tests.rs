@@ -0,0 +1,120 @@
+use std::cell::RefCell;
+
+use wiremock::matchers::{method, path, path_regex};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use super::*;
+use crate::config::{Airflow, Context, Secret};
+use crate::test_utils::{fixture, fixture_json};
+
+const DAG_ID: &str = "example_dynamic_task_mapping";
+const RUN_ID: &str = "manual__2024-03-12T09:30:00+00:00";
+
+fn client(server: &MockServer) -> AirflowClient {
+    AirflowClient::new(&Airflow {
+        host: server.uri(),
+        username: String::from("admin"),
+        password: Secret::Plain(String::from("admin")),
+        ..Airflow::default()
+    })
+    .unwrap()
+}
+
+#[tokio::test]
+async fn export_logs_of_a_mapped_task() {
+    let server = MockServer::start().await;
+    let task_instances = format!("/api/v1/dags/{}/dagRuns/{}/taskInstances", DAG_ID, RUN_ID);
+    Mock::given(method("GET"))
+        .and(path(task_instances.clone()))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_raw(fixture("task_instances_mapped.json"), "application/json"),
+        )
+        .mount(&server)
+        .await;
+    // The second try of the mapped task cannot be fetched
+    Mock::given(method("GET"))
+        .and(path(format!("{}/add_one/logs/2", task_instances)))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path_regex(r"/logs/\d+$"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("log"))
+        .mount(&server)
+        .await;
+
+    let mut dag_run: DagRun = fixture_json("dag_run.json");
+    dag_run.dag_id = String::from(DAG_ID);
+    dag_run.dag_run_id = String::from(RUN_ID);
+    let dir = tempfile::tempdir().unwrap();
+    let messages = RefCell::new(vec![]);
+    let summary = export_logs(&client(&server), &dag_run, dir.path(), |message| {
+        messages.borrow_mut().push(message)
+    })
+    .await
+    .unwrap();
+
+    let run_dir = dir.path().join(DAG_ID).join(path_component(RUN_ID));
+    assert!(run_dir.join("add_one/map_0/try_1.log").is_file());
+    assert!(run_dir.join("add_one/map_1/try_1.log").is_file());
+    assert!(!run_dir.join("add_one/map_1/try_2.log").exists());
+    assert!(run_dir.join("sum_it/try_1.log").is_file());
+    assert_eq!(
+        messages.into_inner(),
+        [
+            "Exporting logs 1/4",
+            "Exporting logs 2/4",
+            "Exporting logs 3/4, 1 failed",
+            "Exporting logs 4/4, 1 failed",
+        ]
+    );
+    assert_eq!(
+        summary,
+        format!(
+            "Exported 3 logs to {}, 1 could not be fetched",
+            run_dir.display()
+        )
+    );
+}
+
+#[tokio::test]
+async fn switch_context_resolves_its_secrets() {
+    let (action_tx, mut action_rx) = tokio::sync::mpsc::unbounded_channel();
+    let worker = Worker::new(
+        AirflowClient::new(&Airflow::default()).unwrap(),
+        10,
+        action_tx,
+    );
+    let airflow = Airflow {
+        host: String::from("http://localhost:8080"),
+        password: Secret::Command {
+            command: String::from("echo admin"),
+        },
+        ..Airflow::default()
+    };
+    let config = Config {
+        contexts: vec![Context {
+            name: String::from("prod"),
+            airflow,
+        }],
+        ..Config::default()
+    };
+
+    worker.switch_context(config.clone(), String::from("prod"));
+    let Some(Action::ContextSwitched(config, client)) = action_rx.recv().await else {
+        panic!("the context was not switched");
+    };
+    assert_eq!(config.current_context, "prod");
+    assert_eq!(
+        config.airflow.password,
+        Secret::Plain(String::from("admin"))
+    );
+    assert_eq!(*client, AirflowClient::new(&config.airflow).unwrap());
+
+    worker.switch_context(*config, String::from("dev"));
+    assert!(matches!(
+        action_rx.recv().await,
+        Some(Action::Error(message)) if message.contains("Unknown context `dev`")
+    ));
+}